@@ -0,0 +1,248 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::{
+	headless,
+	simulation::{
+		cartridge_list, difficulty_list, escort_list, gun_list, level_list, loadout_list, wave_list,
+	},
+};
+
+/// Settings for a batch of balance testing battles
+#[derive(Debug)]
+pub struct BalanceSettings {
+	/// Settings for the first battle. Each battle after it uses the next seed.
+	pub battle_settings: headless::BattleSettings,
+	pub num_battles: usize,
+}
+
+/// Accuracy of one Gun Definition
+#[derive(Serialize, Debug)]
+pub struct GunReport {
+	pub gun_name: gun_list::GunName,
+	pub shots_fired: usize,
+	pub hits: usize,
+	/// Hits per shot fired, None if the gun never fired
+	pub accuracy: Option<f32>,
+}
+
+/// Damage done by one Cartridge Definition
+#[derive(Serialize, Debug)]
+pub struct CartridgeReport {
+	pub projectile_name: cartridge_list::ProjectileName,
+	pub damage_dealt: f32,
+	pub damage_per_second: f32,
+}
+
+/// Results of a single battle
+#[derive(Serialize, Debug)]
+pub struct BattleReport {
+	pub seed: u64,
+	pub survived: bool,
+	pub player_ship_health: f32,
+	pub enemies_destroyed: usize,
+	pub mean_time_to_kill_seconds: Option<f32>,
+	pub guns: Vec<GunReport>,
+	pub cartridges: Vec<CartridgeReport>,
+}
+
+/// Results of a whole batch of battles, with per battle results
+#[derive(Serialize, Debug)]
+pub struct BalanceReport {
+	pub loadout_name: loadout_list::LoadoutName,
+	pub escort_group_name: escort_list::EscortGroupName,
+	pub wave_set_name: wave_list::WaveSetName,
+	pub difficulty_curve_name: difficulty_list::DifficultyCurveName,
+	pub level_name: level_list::LevelName,
+	pub num_battles: usize,
+	pub duration_seconds: f32,
+	pub friendly_fire: bool,
+	/// Fraction of battles the player ship survived
+	pub survival_rate: f32,
+	pub mean_enemies_destroyed: f32,
+	pub mean_time_to_kill_seconds: Option<f32>,
+	pub guns: Vec<GunReport>,
+	pub cartridges: Vec<CartridgeReport>,
+	pub battles: Vec<BattleReport>,
+}
+
+impl BalanceReport {
+	pub fn to_json(&self) -> String {
+		match serde_json::to_string_pretty(self) {
+			Ok(val) => val,
+			Err(error) => panic!("Balance report serialization failed! Error: {}", error),
+		}
+	}
+
+	/// One ```seed,metric,subject,value``` row per number.
+	/// Totals over every battle use ```all``` as the seed.
+	pub fn to_csv(&self) -> String {
+		let mut csv = String::from("seed,metric,subject,value\n");
+		let mut write_rows = |seed: &str,
+		                      survival: f32,
+		                      enemies_destroyed: f32,
+		                      mean_time_to_kill_seconds: Option<f32>,
+		                      guns: &Vec<GunReport>,
+		                      cartridges: &Vec<CartridgeReport>| {
+			csv += &format!("{},survival,,{}\n", seed, survival);
+			csv += &format!("{},enemies_destroyed,,{}\n", seed, enemies_destroyed);
+			if let Some(time_to_kill) = mean_time_to_kill_seconds {
+				csv += &format!("{},time_to_kill_seconds,,{}\n", seed, time_to_kill);
+			}
+			for gun in guns.iter() {
+				csv += &format!(
+					"{},shots_fired,{:?},{}\n",
+					seed, gun.gun_name, gun.shots_fired
+				);
+				csv += &format!("{},hits,{:?},{}\n", seed, gun.gun_name, gun.hits);
+				if let Some(accuracy) = gun.accuracy {
+					csv += &format!("{},accuracy,{:?},{}\n", seed, gun.gun_name, accuracy);
+				}
+			}
+			for cartridge in cartridges.iter() {
+				csv += &format!(
+					"{},damage_dealt,{:?},{}\n",
+					seed, cartridge.projectile_name, cartridge.damage_dealt
+				);
+				csv += &format!(
+					"{},damage_per_second,{:?},{}\n",
+					seed, cartridge.projectile_name, cartridge.damage_per_second
+				);
+			}
+		};
+
+		write_rows(
+			"all",
+			self.survival_rate,
+			self.mean_enemies_destroyed,
+			self.mean_time_to_kill_seconds,
+			&self.guns,
+			&self.cartridges,
+		);
+		for battle in self.battles.iter() {
+			write_rows(
+				&battle.seed.to_string(),
+				if battle.survived { 1.0 } else { 0.0 },
+				battle.enemies_destroyed as f32,
+				battle.mean_time_to_kill_seconds,
+				&battle.guns,
+				&battle.cartridges,
+			);
+		}
+		return csv;
+	}
+}
+
+/// Runs ```num_battles``` headless battles and collects their statistics into a Balance Report
+pub fn run_balance_test(settings: &BalanceSettings) -> BalanceReport {
+	let mut battles = Vec::new();
+	let mut shots_fired = BTreeMap::new();
+	let mut hits = BTreeMap::new();
+	let mut damage_dealt = BTreeMap::new();
+	let mut times_to_kill_seconds = Vec::new();
+	let mut total_duration_seconds = 0.0;
+
+	for battle_number in 0..settings.num_battles {
+		let outcome = headless::run_headless_battle(&headless::BattleSettings {
+			seed: settings.battle_settings.seed + battle_number as u64,
+			..settings.battle_settings.clone()
+		});
+		let battle_statistics = &outcome.statistics;
+
+		// Add to totals
+		for (gun_name, num_shots) in battle_statistics.shots_fired.iter() {
+			*shots_fired.entry(*gun_name).or_insert(0) += num_shots;
+		}
+		for (gun_name, num_hits) in battle_statistics.hits.iter() {
+			*hits.entry(*gun_name).or_insert(0) += num_hits;
+		}
+		for (projectile_name, damage) in battle_statistics.damage_dealt.iter() {
+			*damage_dealt.entry(*projectile_name).or_insert(0.0) += damage;
+		}
+		times_to_kill_seconds.extend(battle_statistics.times_to_kill_seconds.iter());
+		total_duration_seconds += outcome.duration_seconds;
+
+		battles.push(BattleReport {
+			seed: outcome.seed,
+			survived: outcome.player_ship_survived,
+			player_ship_health: outcome.player_ship_health,
+			enemies_destroyed: battle_statistics.enemies_destroyed,
+			mean_time_to_kill_seconds: mean(&battle_statistics.times_to_kill_seconds),
+			guns: gun_reports(&battle_statistics.shots_fired, &battle_statistics.hits),
+			cartridges: cartridge_reports(
+				&battle_statistics.damage_dealt,
+				outcome.duration_seconds,
+			),
+		});
+	}
+
+	let num_battles = settings.num_battles.max(1) as f32;
+	return BalanceReport {
+		loadout_name: settings.battle_settings.loadout_name,
+		escort_group_name: settings.battle_settings.escort_group_name,
+		wave_set_name: settings.battle_settings.wave_set_name,
+		difficulty_curve_name: settings.battle_settings.difficulty_curve_name,
+		level_name: settings.battle_settings.level_name,
+		num_battles: settings.num_battles,
+		duration_seconds: settings.battle_settings.duration_seconds,
+		friendly_fire: settings.battle_settings.friendly_fire,
+		survival_rate: battles.iter().filter(|battle| battle.survived).count() as f32 / num_battles,
+		mean_enemies_destroyed: battles
+			.iter()
+			.map(|battle| battle.enemies_destroyed)
+			.sum::<usize>() as f32
+			/ num_battles,
+		mean_time_to_kill_seconds: mean(&times_to_kill_seconds),
+		guns: gun_reports(&shots_fired, &hits),
+		cartridges: cartridge_reports(&damage_dealt, total_duration_seconds),
+		battles,
+	};
+}
+
+fn mean(values: &[f32]) -> Option<f32> {
+	if values.is_empty() {
+		return None;
+	}
+	return Some(values.iter().sum::<f32>() / values.len() as f32);
+}
+
+fn gun_reports(
+	shots_fired: &BTreeMap<gun_list::GunName, usize>,
+	hits: &BTreeMap<gun_list::GunName, usize>,
+) -> Vec<GunReport> {
+	let mut gun_reports = Vec::new();
+	for (gun_name, num_shots) in shots_fired.iter() {
+		let num_hits = *hits.get(gun_name).unwrap_or(&0);
+		gun_reports.push(GunReport {
+			gun_name: *gun_name,
+			shots_fired: *num_shots,
+			hits: num_hits,
+			accuracy: if *num_shots > 0 {
+				Some(num_hits as f32 / *num_shots as f32)
+			} else {
+				None
+			},
+		});
+	}
+	return gun_reports;
+}
+
+fn cartridge_reports(
+	damage_dealt: &BTreeMap<cartridge_list::ProjectileName, f32>,
+	duration_seconds: f32,
+) -> Vec<CartridgeReport> {
+	let mut cartridge_reports = Vec::new();
+	for (projectile_name, damage) in damage_dealt.iter() {
+		cartridge_reports.push(CartridgeReport {
+			projectile_name: *projectile_name,
+			damage_dealt: *damage,
+			damage_per_second: if duration_seconds > 0.0 {
+				damage / duration_seconds
+			} else {
+				0.0
+			},
+		});
+	}
+	return cartridge_reports;
+}
@@ -0,0 +1,85 @@
+//! Runs a batch of seeded headless battles and prints balance statistics as CSV or JSON.
+//!
+//! Usage: balance [--loadout NAME] [--escorts ESCORT_GROUP_NAME] [--waves WAVE_SET_NAME]
+//! [--difficulty CURVE_NAME] [--level LEVEL_NAME] [--battles N] [--seed FIRST_SEED]
+//! [--duration SECONDS] [--friendly-fire true|false] [--format csv|json]
+
+use smatter::{balance, headless};
+
+fn main() {
+	let mut settings = balance::BalanceSettings {
+		battle_settings: headless::BattleSettings::default(),
+		num_battles: 10,
+	};
+	let mut output_json = false;
+
+	let args: Vec<String> = std::env::args().skip(1).collect();
+	let mut args = args.iter();
+	while let Some(arg) = args.next() {
+		let value = match args.next() {
+			Some(val) => val,
+			None => panic!("Missing value for argument {}!", arg),
+		};
+		match arg.as_str() {
+			"--loadout" => {
+				settings.battle_settings.loadout_name = serde_yaml::from_str(value)
+					.unwrap_or_else(|error| panic!("Unknown loadout {}! Error: {}", value, error))
+			}
+			"--escorts" => {
+				settings.battle_settings.escort_group_name = serde_yaml::from_str(value)
+					.unwrap_or_else(|error| {
+						panic!("Unknown escort group {}! Error: {}", value, error)
+					})
+			}
+			"--waves" => {
+				settings.battle_settings.wave_set_name = serde_yaml::from_str(value)
+					.unwrap_or_else(|error| panic!("Unknown wave set {}! Error: {}", value, error))
+			}
+			"--difficulty" => {
+				settings.battle_settings.difficulty_curve_name = serde_yaml::from_str(value)
+					.unwrap_or_else(|error| {
+						panic!("Unknown difficulty curve {}! Error: {}", value, error)
+					})
+			}
+			"--level" => {
+				settings.battle_settings.level_name = serde_yaml::from_str(value)
+					.unwrap_or_else(|error| panic!("Unknown level {}! Error: {}", value, error))
+			}
+			"--battles" => {
+				settings.num_battles = value.parse().unwrap_or_else(|error| {
+					panic!("Invalid battle count {}! Error: {}", value, error)
+				})
+			}
+			"--seed" => {
+				settings.battle_settings.seed = value
+					.parse()
+					.unwrap_or_else(|error| panic!("Invalid seed {}! Error: {}", value, error))
+			}
+			"--duration" => {
+				settings.battle_settings.duration_seconds = value
+					.parse()
+					.unwrap_or_else(|error| panic!("Invalid duration {}! Error: {}", value, error))
+			}
+			"--friendly-fire" => {
+				settings.battle_settings.friendly_fire = value.parse().unwrap_or_else(|error| {
+					panic!("Invalid friendly fire setting {}! Error: {}", value, error)
+				})
+			}
+			"--format" => {
+				output_json = match value.as_str() {
+					"csv" => false,
+					"json" => true,
+					_ => panic!("Unknown format {}! Expected csv or json.", value),
+				}
+			}
+			_ => panic!("Unknown argument {}!", arg),
+		}
+	}
+
+	let balance_report = balance::run_balance_test(&settings);
+	if output_json {
+		println!("{}", balance_report.to_json());
+	} else {
+		print!("{}", balance_report.to_csv());
+	}
+}
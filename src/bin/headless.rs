@@ -0,0 +1,22 @@
+//! Runs one battle without a window, audio or rendering and prints the outcome.
+//!
+//! Usage: headless [seed] [duration_seconds]
+
+fn main() {
+	let mut battle_settings = smatter::headless::BattleSettings::default();
+
+	let args: Vec<String> = std::env::args().collect();
+	if let Some(val) = args.get(1) {
+		battle_settings.seed = val
+			.parse::<u64>()
+			.unwrap_or_else(|error| panic!("Invalid seed {}! Error: {}", val, error));
+	}
+	if let Some(val) = args.get(2) {
+		battle_settings.duration_seconds = val
+			.parse::<f32>()
+			.unwrap_or_else(|error| panic!("Invalid duration {}! Error: {}", val, error));
+	}
+
+	let outcome = smatter::headless::run_headless_battle(&battle_settings);
+	println!("{:#?}", outcome);
+}
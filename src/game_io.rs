@@ -15,7 +15,7 @@ pub fn read_definitions<T: for<'de> serde::Deserialize<'de>>(path: &str) -> Vec<
 		let definition_path = match definition_result {
 			Ok(val) => {
 				if val.file_name() == "template.yaml" {
-					break; // Skip templates
+					continue; // Skip templates
 				} else {
 					val.path()
 				}
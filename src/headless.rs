@@ -0,0 +1,227 @@
+use bevy::{prelude::*, transform::TransformPlugin};
+
+use crate::simulation::{
+	self, difficulty_list, escort_list, interaction, level, level_list, loadout_list, ship,
+	spawning, statistics, wave, wave_list,
+};
+
+/// What to fight in a headless battle
+#[derive(Clone, Debug)]
+pub struct BattleSettings {
+	pub seed: u64,
+	/// Loadout the player ship is fitted with
+	pub loadout_name: loadout_list::LoadoutName,
+	/// Escorts accompanying the player ship
+	pub escort_group_name: escort_list::EscortGroupName,
+	pub wave_set_name: wave_list::WaveSetName,
+	pub difficulty_curve_name: difficulty_list::DifficultyCurveName,
+	/// Level whose track the player ship flies along
+	pub level_name: level_list::LevelName,
+	/// Simulated time to run the battle for
+	pub duration_seconds: f32,
+	/// Whether projectiles and blasts damage ships of allied factions
+	pub friendly_fire: bool,
+}
+
+impl Default for BattleSettings {
+	fn default() -> Self {
+		Self {
+			seed: simulation::DEFAULT_SIMULATION_SEED,
+			loadout_name: default(),
+			escort_group_name: default(),
+			wave_set_name: default(),
+			difficulty_curve_name: default(),
+			level_name: default(),
+			duration_seconds: 60.0,
+			friendly_fire: false,
+		}
+	}
+}
+
+/// Summary of a headless battle
+#[derive(Debug, PartialEq)]
+pub struct BattleOutcome {
+	pub seed: u64,
+	pub duration_seconds: f32,
+	pub player_ship_survived: bool,
+	pub player_ship_health: f32,
+	/// Whether the level's stronghold was defeated
+	pub stage_complete: bool,
+	pub statistics: statistics::BattleStatistics,
+}
+
+/// Builds an app that runs only the simulation: no window, audio or rendering.
+/// Each ```App::update``` advances the simulation by exactly one tick.
+pub fn build_headless_app(seed: u64) -> App {
+	let mut app = App::new();
+	app.add_plugins(MinimalPlugins)
+		.add_plugin(TransformPlugin)
+		.insert_resource(simulation::SimulationRng::from_seed(seed))
+		.add_plugins(simulation::SimulationPlugins {
+			tick_every_update: true,
+		})
+		.add_plugin(statistics::StatisticsPlugin);
+	return app;
+}
+
+/// Builds a headless app set up to fight the given battle
+pub fn build_battle_app(battle_settings: &BattleSettings) -> App {
+	let mut app = build_headless_app(battle_settings.seed);
+	app.insert_resource(spawning::PlayerLoadout(battle_settings.loadout_name))
+		.insert_resource(spawning::PlayerEscortGroup(
+			battle_settings.escort_group_name,
+		))
+		.insert_resource(wave::WaveDirector::new(battle_settings.wave_set_name))
+		.insert_resource(spawning::DifficultyCurve(
+			battle_settings.difficulty_curve_name,
+		))
+		.insert_resource(level::CurrentLevel(battle_settings.level_name))
+		.insert_resource(interaction::FriendlyFire(battle_settings.friendly_fire));
+	return app;
+}
+
+/// Number of simulation ticks a battle lasts
+fn battle_ticks(battle_settings: &BattleSettings) -> usize {
+	return (battle_settings.duration_seconds as f64 / simulation::SIMULATION_TIMESTEP).ceil()
+		as usize;
+}
+
+/// Runs a seeded battle and reports how it went
+pub fn run_headless_battle(battle_settings: &BattleSettings) -> BattleOutcome {
+	let mut app = build_battle_app(battle_settings);
+	for _ in 0..battle_ticks(battle_settings) {
+		app.update();
+		if app.world.resource::<level::LevelProgress>().stage_complete {
+			break;
+		}
+	}
+	return battle_outcome(&mut app.world, battle_settings.seed);
+}
+
+/// Takes the ```BattleOutcome``` out of a headless app's world
+pub fn battle_outcome(world: &mut World, seed: u64) -> BattleOutcome {
+	let player_ship = world
+		.query_filtered::<(&ship::Health, Option<&ship::IsDestroyed>), With<ship::IsPlayerShip>>()
+		.iter(world)
+		.next()
+		.map(|(health, destroyed)| (health.0, destroyed.is_none()));
+	let battle_statistics = world
+		.remove_resource::<statistics::BattleStatistics>()
+		.expect("Headless app is missing its BattleStatistics!");
+	let stage_complete = world.resource::<level::LevelProgress>().stage_complete;
+
+	return BattleOutcome {
+		seed,
+		duration_seconds: battle_statistics.elapsed_seconds(),
+		player_ship_survived: player_ship.is_some_and(|(_, survived)| survived),
+		player_ship_health: player_ship.map_or(0.0, |(health, _)| health.max(0.0)),
+		stage_complete,
+		statistics: battle_statistics,
+	};
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::simulation::{cartridge_list, enemy_list, gun_list, ship_list};
+
+	/// Long enough for the first waves to trade fire with the player
+	const TEST_BATTLE_SECONDS: f32 = 20.0;
+
+	fn test_battle_settings() -> BattleSettings {
+		return BattleSettings {
+			seed: 7,
+			duration_seconds: TEST_BATTLE_SECONDS,
+			..default()
+		};
+	}
+
+	/// Runs a battle with several simulation ticks per app update,
+	/// the way a slow frame catches up on the fixed timestep
+	fn run_battle_catching_up(
+		battle_settings: &BattleSettings,
+		ticks_per_update: usize,
+	) -> BattleOutcome {
+		let mut app = build_battle_app(battle_settings);
+		for _ in 0..battle_ticks(battle_settings) / ticks_per_update {
+			app.update();
+			for _ in 1..ticks_per_update {
+				app.schedule
+					.get_stage_mut::<Schedule>(&simulation::SimulationStage)
+					.expect("Headless app is missing its simulation schedule!")
+					.run(&mut app.world);
+			}
+		}
+		return battle_outcome(&mut app.world, battle_settings.seed);
+	}
+
+	/// Spawns one invulnerable Gunship a little way ahead of the player ship
+	fn spawn_lone_gunship(
+		mut commands: Commands,
+		enemy_definition_list: Res<enemy_list::EnemyDefinitionList>,
+		ship_definition_list: Res<ship_list::ShipDefinitionList>,
+		gun_definition_list: Res<gun_list::GunDefinitionList>,
+		cartridge_definition_list: Res<cartridge_list::CartridgeDefinitionList>,
+	) {
+		let gunship = spawning::spawn_enemy(
+			enemy_list::EnemyName::Gunship,
+			Transform::from_translation(
+				Vec3::from(spawning::PLAYER_SPAWN_TRANSLATION) + Vec3::new(0.0, 300.0, 0.0),
+			),
+			&enemy_definition_list,
+			&ship_definition_list,
+			&gun_definition_list,
+			&cartridge_definition_list,
+		);
+		commands
+			.spawn_bundle(gunship)
+			.insert(interaction::Invulnerable);
+	}
+
+	fn player_ship_health(world: &mut World) -> f32 {
+		return world
+			.query_filtered::<&ship::Health, With<ship::IsPlayerShip>>()
+			.iter(world)
+			.next()
+			.expect("Headless app is missing its player ship!")
+			.0;
+	}
+
+	#[test]
+	fn lone_enemy_damages_player_hull() {
+		let mut app = build_battle_app(&BattleSettings {
+			escort_group_name: escort_list::EscortGroupName::Unescorted,
+			..test_battle_settings()
+		});
+		app.add_startup_system(spawn_lone_gunship);
+		// Keep the wave director from sending anything else
+		app.world
+			.resource_mut::<level::LevelProgress>()
+			.stage_complete = true;
+
+		app.update();
+		let starting_health = player_ship_health(&mut app.world);
+		for _ in 0..battle_ticks(&test_battle_settings()) {
+			app.update();
+		}
+		assert!(player_ship_health(&mut app.world) < starting_health);
+	}
+
+	#[test]
+	fn same_seed_same_battle() {
+		let battle_settings = test_battle_settings();
+		let first = run_headless_battle(&battle_settings);
+		let second = run_headless_battle(&battle_settings);
+		assert!(first.statistics.ticks > 0);
+		assert_eq!(first.statistics, second.statistics);
+		assert_eq!(first, second);
+	}
+
+	#[test]
+	fn ticks_per_frame_do_not_change_the_battle() {
+		let battle_settings = test_battle_settings();
+		let one_tick_per_update = run_battle_catching_up(&battle_settings, 1);
+		let three_ticks_per_update = run_battle_catching_up(&battle_settings, 3);
+		assert_eq!(one_tick_per_update, three_ticks_per_update);
+	}
+}
@@ -0,0 +1,53 @@
+// Explicit returns are the house style, Bevy 0.7's derives trip forget_non_drop,
+// and systems take as many queries and resources as they need
+#![allow(
+	clippy::needless_return,
+	clippy::derivable_impls,
+	clippy::upper_case_acronyms,
+	clippy::forget_non_drop,
+	clippy::too_many_arguments,
+	clippy::type_complexity
+)]
+
+use bevy::prelude::*;
+
+pub mod balance;
+pub mod game_io;
+pub mod headless;
+pub mod player_input;
+pub mod presentation;
+pub mod simulation;
+
+/// Adds the whole game on top of Bevy's default plugins.
+/// Tools and test harnesses can instead pick from the individual simulation plugins,
+/// starting with ```simulation::SimulationPlugin```, or use ```headless::build_headless_app```.
+pub struct SmatterPlugin;
+
+impl Plugin for SmatterPlugin {
+	fn build(&self, app: &mut App) {
+		app
+			// Write out definition templates
+			.add_startup_system(simulation::faction_list::write_faction_definition_template)
+			.add_startup_system(simulation::cartridge_list::write_cartridge_definition_template)
+			.add_startup_system(simulation::gun_list::write_gun_definition_template)
+			.add_startup_system(simulation::ship_list::write_ship_definition_template)
+			.add_startup_system(simulation::loadout_list::write_loadout_definition_template)
+			.add_startup_system(simulation::upgrade_list::write_upgrade_definition_template)
+			.add_startup_system(simulation::enemy_list::write_enemy_definition_template)
+			.add_startup_system(simulation::escort_list::write_escort_group_definition_template)
+			.add_startup_system(simulation::formation_list::write_formation_definition_template)
+			.add_startup_system(simulation::wave_list::write_wave_set_definition_template)
+			.add_startup_system(
+				simulation::difficulty_list::write_difficulty_curve_definition_template,
+			)
+			.add_startup_system(simulation::level_list::write_level_definition_template)
+			.add_startup_system(simulation::stronghold_list::write_stronghold_definition_template)
+			// Simulation
+			.add_plugins(simulation::SimulationPlugins::default())
+			// Input
+			.add_plugin(player_input::PlayerInputPlugin)
+			// Presentation
+			.add_plugin(presentation::PresentationPlugin)
+			.add_plugin(simulation::camera::CameraPlugin);
+	}
+}
@@ -0,0 +1,156 @@
+use bevy::prelude::*;
+
+use crate::simulation::{escort, faction_list, interaction, level, projectile, ship};
+
+/// How quickly holding a throttle key or button moves the throttle, as a fraction per second
+const THROTTLE_CHANGE_PER_SECOND: f32 = 0.5;
+/// Gamepad stick deflection ignored before the throttle starts moving
+const GAMEPAD_STICK_DEADZONE: f32 = 0.2;
+
+/// Turns keyboard and gamepad input into orders for the player ship.
+/// Left out of headless apps, which have no player at the controls.
+pub struct PlayerInputPlugin;
+
+impl Plugin for PlayerInputPlugin {
+	fn build(&self, app: &mut App) {
+		app.add_system(player_throttle_input_system)
+			.add_system(escort_order_input_system)
+			.add_system(friendly_fire_input_system);
+	}
+}
+
+// ==========
+// Systems
+
+/// Adjusts the player ship's Throttle.
+/// Keyboard: W/Up speeds up, S/Down slows down, X orders all stop.
+/// Gamepad: D-pad up/down or the left stick.
+pub fn player_throttle_input_system(
+	time: Res<Time>,
+	keyboard: Res<Input<KeyCode>>,
+	gamepads: Res<Gamepads>,
+	gamepad_buttons: Res<Input<GamepadButton>>,
+	gamepad_axes: Res<Axis<GamepadAxis>>,
+	mut player_ship: Query<
+		&mut level::Throttle,
+		(With<ship::IsPlayerShip>, Without<ship::IsDestroyed>),
+	>,
+) {
+	let mut throttle = match player_ship.get_single_mut() {
+		Ok(val) => val,
+		Err(_) => return, // No player ship to give orders to
+	};
+
+	if keyboard.just_pressed(KeyCode::X) {
+		throttle.0 = 0.0;
+		return;
+	}
+
+	let mut throttle_input = 0.0;
+	if keyboard.pressed(KeyCode::W) || keyboard.pressed(KeyCode::Up) {
+		throttle_input += 1.0;
+	}
+	if keyboard.pressed(KeyCode::S) || keyboard.pressed(KeyCode::Down) {
+		throttle_input -= 1.0;
+	}
+	for gamepad in gamepads.iter() {
+		if gamepad_buttons.pressed(GamepadButton(*gamepad, GamepadButtonType::DPadUp)) {
+			throttle_input += 1.0;
+		}
+		if gamepad_buttons.pressed(GamepadButton(*gamepad, GamepadButtonType::DPadDown)) {
+			throttle_input -= 1.0;
+		}
+		if let Some(stick_y) = gamepad_axes.get(GamepadAxis(*gamepad, GamepadAxisType::LeftStickY))
+		{
+			if stick_y.abs() > GAMEPAD_STICK_DEADZONE {
+				throttle_input += stick_y;
+			}
+		}
+	}
+
+	if throttle_input != 0.0 {
+		throttle.adjust(
+			throttle_input.clamp(-1.0, 1.0) * THROTTLE_CHANGE_PER_SECOND * time.delta_seconds(),
+		);
+	}
+}
+
+/// Gives every escort the same order.
+/// Keyboard: 1 screens, 2 guards, 3 attacks the hostile ship nearest the player ship.
+/// Gamepad: D-pad left screens, D-pad right guards, North attacks.
+pub fn escort_order_input_system(
+	keyboard: Res<Input<KeyCode>>,
+	gamepads: Res<Gamepads>,
+	gamepad_buttons: Res<Input<GamepadButton>>,
+	faction_definition_list: Res<faction_list::FactionDefinitionList>,
+	player_ship: Query<(&Transform, &faction_list::FactionName), With<ship::IsPlayerShip>>,
+	targets: Query<
+		(Entity, &Transform, &faction_list::FactionName),
+		(
+			With<ship::Health>,
+			Without<ship::IsDestroyed>,
+			Without<projectile::IsProjectile>,
+		),
+	>,
+	mut escorts: Query<&mut escort::EscortOrder, With<escort::IsEscortShip>>,
+) {
+	let gamepad_pressed = |button_type| {
+		gamepads
+			.iter()
+			.any(|gamepad| gamepad_buttons.just_pressed(GamepadButton(*gamepad, button_type)))
+	};
+
+	let order = if keyboard.just_pressed(KeyCode::Key1)
+		|| gamepad_pressed(GamepadButtonType::DPadLeft)
+	{
+		escort::EscortOrder::Screen
+	} else if keyboard.just_pressed(KeyCode::Key2) || gamepad_pressed(GamepadButtonType::DPadRight)
+	{
+		escort::EscortOrder::Guard
+	} else if keyboard.just_pressed(KeyCode::Key3) || gamepad_pressed(GamepadButtonType::North) {
+		let (player_transform, player_faction) = match player_ship.get_single() {
+			Ok(val) => val,
+			Err(_) => return,
+		};
+		let nearest_hostile = targets
+			.iter()
+			.filter(|(_, _, faction)| {
+				faction_definition_list.is_hostile(*player_faction, **faction)
+			})
+			.map(|(entity, transform, _)| {
+				(
+					entity,
+					transform
+						.translation
+						.distance_squared(player_transform.translation),
+				)
+			})
+			.min_by(|(_, distance_a), (_, distance_b)| distance_a.total_cmp(distance_b));
+		match nearest_hostile {
+			Some((target, _)) => escort::EscortOrder::AttackTarget(target),
+			None => return, // Nothing to attack
+		}
+	} else {
+		return;
+	};
+
+	for mut escort_order in escorts.iter_mut() {
+		*escort_order = order;
+	}
+}
+
+/// Toggles Friendly Fire.
+/// Keyboard: F. Gamepad: Select.
+pub fn friendly_fire_input_system(
+	keyboard: Res<Input<KeyCode>>,
+	gamepads: Res<Gamepads>,
+	gamepad_buttons: Res<Input<GamepadButton>>,
+	mut friendly_fire: ResMut<interaction::FriendlyFire>,
+) {
+	if keyboard.just_pressed(KeyCode::F)
+		|| gamepads.iter().any(|gamepad| {
+			gamepad_buttons.just_pressed(GamepadButton(*gamepad, GamepadButtonType::Select))
+		}) {
+		friendly_fire.0 = !friendly_fire.0;
+	}
+}
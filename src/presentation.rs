@@ -0,0 +1,388 @@
+use bevy::prelude::*;
+
+use crate::simulation::{self, destruction, gun, interaction, level, shield, ship, turret};
+
+/// Draws simulation entities and plays their sounds.
+/// Left out of headless apps, which only run the simulation.
+pub struct PresentationPlugin;
+
+impl Plugin for PresentationPlugin {
+	fn build(&self, app: &mut App) {
+		app
+			// PostUpdate so entities spawned by the simulation this frame are drawn this frame
+			.add_system_to_stage(CoreStage::PostUpdate, appearance_sprite_system)
+			.add_system(gun_fire_sound_system)
+			.add_system(game_over_system)
+			.add_system(explosion_spawn_system)
+			.add_system(explosion_animation_system)
+			.add_system(detonation_flash_system)
+			.add_system(debris_fade_system)
+			.add_system_to_stage(CoreStage::PostUpdate, shield_bubble_spawn_system)
+			.add_system(shield_bubble_system)
+			.add_system_to_stage(CoreStage::PostUpdate, turret_status_spawn_system)
+			.add_system(turret_status_system)
+			.add_startup_system(setup_hud)
+			.add_system(hud_system);
+	}
+}
+
+/// Width of the HUD bars in pixels
+const HUD_BAR_WIDTH: f32 = 200.0;
+/// Height of the HUD bars in pixels
+const HUD_BAR_HEIGHT: f32 = 12.0;
+/// How much bigger than its ship a shield bubble is drawn
+const SHIELD_BUBBLE_SCALE: f32 = 1.3;
+/// Opacity of a fully charged shield bubble
+const SHIELD_BUBBLE_ALPHA: f32 = 0.35;
+/// Frames a detonation flash fades over
+const DETONATION_FLASH_FRAMES: usize = 4;
+/// Seconds each frame of a detonation flash lasts
+const DETONATION_FLASH_FRAME_SECONDS: f32 = 0.04;
+/// Size of the status light on each turret mount, in meters
+const TURRET_STATUS_SIZE: f32 = 4.0;
+
+// ==========
+// Components
+
+/// A playing explosion animation
+#[derive(Component)]
+pub struct Explosion {
+	pub frame_timer: Timer,
+	pub frame: usize,
+	pub frames: usize,
+}
+
+/// Bubble drawn around a shielded ship, fading as its Shield weakens
+#[derive(Component)]
+pub struct ShieldBubble;
+
+/// Light on a turret mount, green to red with the turret's health, and grey once it is offline
+#[derive(Component)]
+pub struct TurretStatusIndicator;
+
+/// Fill of the HUD bar showing progress towards the stronghold
+#[derive(Component)]
+pub struct LevelProgressBarFill;
+
+/// Fill of the HUD bar showing the player ship's throttle
+#[derive(Component)]
+pub struct ThrottleBarFill;
+
+// ==========
+// Systems
+
+/// Spawns the UI camera and the level progress and throttle bars
+pub fn setup_hud(mut commands: Commands) {
+	commands.spawn_bundle(UiCameraBundle::default());
+	spawn_hud_bar(
+		&mut commands,
+		10.0,
+		Color::rgb(0.3, 0.6, 1.0),
+		LevelProgressBarFill,
+	);
+	spawn_hud_bar(
+		&mut commands,
+		30.0,
+		Color::rgb(1.0, 0.7, 0.2),
+		ThrottleBarFill,
+	);
+}
+
+/// Spawns a bar in the top left of the screen, ```top``` pixels down, with a fill marked by ```marker```
+fn spawn_hud_bar(commands: &mut Commands, top: f32, color: Color, marker: impl Component) {
+	commands
+		.spawn_bundle(NodeBundle {
+			style: Style {
+				position_type: PositionType::Absolute,
+				position: Rect {
+					left: Val::Px(10.0),
+					top: Val::Px(top),
+					..default()
+				},
+				size: Size::new(Val::Px(HUD_BAR_WIDTH), Val::Px(HUD_BAR_HEIGHT)),
+				..default()
+			},
+			color: UiColor(Color::rgba(0.1, 0.1, 0.1, 0.8)),
+			..default()
+		})
+		.with_children(|parent| {
+			parent
+				.spawn_bundle(NodeBundle {
+					style: Style {
+						size: Size::new(Val::Percent(0.0), Val::Percent(100.0)),
+						..default()
+					},
+					color: UiColor(color),
+					..default()
+				})
+				.insert(marker);
+		});
+}
+
+/// Fills the HUD bars from the level progress and the player ship's throttle
+pub fn hud_system(
+	level_progress: Res<level::LevelProgress>,
+	player_ship: Query<&level::Throttle, With<ship::IsPlayerShip>>,
+	mut progress_bar: Query<&mut Style, (With<LevelProgressBarFill>, Without<ThrottleBarFill>)>,
+	mut throttle_bar: Query<&mut Style, (With<ThrottleBarFill>, Without<LevelProgressBarFill>)>,
+) {
+	if let Ok(mut style) = progress_bar.get_single_mut() {
+		style.size.width = Val::Percent(level_progress.fraction() * 100.0);
+	}
+	if let (Ok(mut style), Ok(throttle)) = (throttle_bar.get_single_mut(), player_ship.get_single())
+	{
+		style.size.width = Val::Percent(throttle.0 * 100.0);
+	}
+}
+
+/// Gives newly spawned entities with an Appearance the sprite it describes
+pub fn appearance_sprite_system(
+	mut commands: Commands,
+	asset_server: Res<AssetServer>,
+	new_appearances: Query<(Entity, &simulation::Appearance), Added<simulation::Appearance>>,
+) {
+	for (entity, appearance) in new_appearances.iter() {
+		let texture: Handle<Image> = match &appearance.texture_path {
+			Some(texture_path) => asset_server.load(texture_path.as_str()),
+			None => bevy::render::texture::DEFAULT_IMAGE_HANDLE.typed(),
+		};
+		commands.entity(entity).insert_bundle((
+			Sprite {
+				custom_size: Some(appearance.size),
+				color: appearance.color,
+				..default()
+			},
+			texture,
+			Visibility::default(),
+		));
+	}
+}
+
+/// Plays the fire sound of every gun that fired
+pub fn gun_fire_sound_system(
+	audio: Res<Audio>,
+	asset_server: Res<AssetServer>,
+	mut gun_fired_events: EventReader<gun::GunFiredEvent>,
+	turrets: Query<&gun::GunProperties>,
+) {
+	for gun_fired_event in gun_fired_events.iter() {
+		if let Ok(gun_properties) = turrets.get(gun_fired_event.turret) {
+			audio.play(asset_server.load(gun_properties.fire_sound_path.as_str()));
+		}
+	}
+}
+
+/// Greys out the player ship when the game is over
+pub fn game_over_system(
+	mut game_over_events: EventReader<interaction::GameOverEvent>,
+	mut sprites: Query<&mut Sprite>,
+) {
+	for game_over_event in game_over_events.iter() {
+		if let Ok(mut sprite) = sprites.get_mut(game_over_event.player_ship) {
+			sprite.color = Color::DARK_GRAY;
+		}
+	}
+}
+
+/// Plays the explosion animation and sound of everything destroyed
+pub fn explosion_spawn_system(
+	mut commands: Commands,
+	audio: Res<Audio>,
+	asset_server: Res<AssetServer>,
+	mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+	mut destroyed_events: EventReader<interaction::DestroyedEvent>,
+) {
+	for destroyed_event in destroyed_events.iter() {
+		let destruction_effect = match &destroyed_event.destruction_effect {
+			Some(val) => val,
+			None => continue,
+		};
+		if let Some(sound_path) = &destruction_effect.explosion_sound_path {
+			audio.play(asset_server.load(sound_path.as_str()));
+		}
+
+		// Draw above the wreck
+		let transform = Transform::from_translation(destroyed_event.translation + Vec3::Z * 50.0);
+		let explosion = Explosion {
+			frame_timer: Timer::from_seconds(destruction_effect.explosion_frame_seconds, true),
+			frame: 0,
+			frames: destruction_effect.explosion_frames.max(1),
+		};
+		match &destruction_effect.explosion_texture_path {
+			Some(texture_path) => {
+				let texture_atlas = TextureAtlas::from_grid(
+					asset_server.load(texture_path.as_str()),
+					destruction_effect.explosion_frame_size,
+					explosion.frames,
+					1,
+				);
+				commands
+					.spawn_bundle(SpriteSheetBundle {
+						texture_atlas: texture_atlases.add(texture_atlas),
+						sprite: TextureAtlasSprite {
+							custom_size: Some(destruction_effect.explosion_size),
+							..default()
+						},
+						transform,
+						..default()
+					})
+					.insert(explosion);
+			}
+			None => {
+				commands
+					.spawn_bundle(SpriteBundle {
+						sprite: Sprite {
+							custom_size: Some(destruction_effect.explosion_size),
+							color: Color::ORANGE,
+							..default()
+						},
+						transform,
+						..default()
+					})
+					.insert(explosion);
+			}
+		}
+	}
+}
+
+/// Shows a brief flash the size of the blast wherever a projectile detonates
+pub fn detonation_flash_system(
+	mut commands: Commands,
+	mut detonation_events: EventReader<interaction::DetonationEvent>,
+) {
+	for detonation_event in detonation_events.iter() {
+		commands
+			.spawn_bundle(SpriteBundle {
+				sprite: Sprite {
+					custom_size: Some(Vec2::splat(detonation_event.blast.radius * 2.0)),
+					color: Color::rgba(1.0, 0.8, 0.4, 0.6),
+					..default()
+				},
+				// Draw above the ships caught in it
+				transform: Transform::from_translation(detonation_event.translation.extend(50.0)),
+				..default()
+			})
+			.insert(Explosion {
+				frame_timer: Timer::from_seconds(DETONATION_FLASH_FRAME_SECONDS, true),
+				frame: 0,
+				frames: DETONATION_FLASH_FRAMES,
+			});
+	}
+}
+
+/// Steps explosions through their frames, and removes them once they finish.
+/// Explosions without a sprite sheet fade out instead.
+pub fn explosion_animation_system(
+	mut commands: Commands,
+	time: Res<Time>,
+	mut explosions: Query<(
+		Entity,
+		&mut Explosion,
+		Option<&mut TextureAtlasSprite>,
+		Option<&mut Sprite>,
+	)>,
+) {
+	for (entity, mut explosion, atlas_sprite, sprite) in explosions.iter_mut() {
+		explosion.frame_timer.tick(time.delta());
+		explosion.frame += explosion.frame_timer.times_finished() as usize;
+		if explosion.frame >= explosion.frames {
+			commands.entity(entity).despawn();
+			continue;
+		}
+		if let Some(mut atlas_sprite) = atlas_sprite {
+			atlas_sprite.index = explosion.frame;
+		}
+		if let Some(mut sprite) = sprite {
+			sprite
+				.color
+				.set_a(1.0 - explosion.frame as f32 / explosion.frames as f32);
+		}
+	}
+}
+
+/// Fades debris out over its lifetime
+pub fn debris_fade_system(mut debris: Query<(&destruction::Debris, &mut Sprite)>) {
+	for (debris, mut sprite) in debris.iter_mut() {
+		sprite.color.set_a(debris.fraction_remaining());
+	}
+}
+
+/// Gives newly spawned ships with a shield a bubble around them
+pub fn shield_bubble_spawn_system(
+	mut commands: Commands,
+	new_shields: Query<(Entity, &shield::Shield, &simulation::Appearance), Added<shield::Shield>>,
+) {
+	for (entity, shield, appearance) in new_shields.iter() {
+		if shield.definition.capacity <= 0.0 {
+			continue;
+		}
+		let diameter = appearance.size.max_element() * SHIELD_BUBBLE_SCALE;
+		let bubble = commands
+			.spawn_bundle(SpriteBundle {
+				sprite: Sprite {
+					custom_size: Some(Vec2::splat(diameter)),
+					color: Color::rgba(0.3, 0.9, 1.0, SHIELD_BUBBLE_ALPHA),
+					..default()
+				},
+				// Draw above the ship
+				transform: Transform::from_translation(Vec3::Z * 10.0),
+				..default()
+			})
+			.insert(ShieldBubble)
+			.id();
+		commands.entity(entity).add_child(bubble);
+	}
+}
+
+/// Fades shield bubbles with their Shield's strength, and hides them while it is down
+pub fn shield_bubble_system(
+	mut bubbles: Query<(&Parent, &mut Sprite, &mut Visibility), With<ShieldBubble>>,
+	shields: Query<&shield::Shield>,
+) {
+	for (parent, mut sprite, mut visibility) in bubbles.iter_mut() {
+		if let Ok(shield) = shields.get(parent.0) {
+			visibility.is_visible = shield.is_up();
+			sprite.color.set_a(SHIELD_BUBBLE_ALPHA * shield.fraction());
+		}
+	}
+}
+
+/// Gives newly spawned turrets a status light on their mount
+pub fn turret_status_spawn_system(
+	mut commands: Commands,
+	new_turrets: Query<Entity, Added<turret::IsTurret>>,
+) {
+	for turret_entity in new_turrets.iter() {
+		let indicator = commands
+			.spawn_bundle(SpriteBundle {
+				sprite: Sprite {
+					custom_size: Some(Vec2::splat(TURRET_STATUS_SIZE)),
+					color: Color::GREEN,
+					..default()
+				},
+				// Draw above the turret and its guns
+				transform: Transform::from_translation(Vec3::Z * 5.0),
+				..default()
+			})
+			.insert(TurretStatusIndicator)
+			.id();
+		commands.entity(turret_entity).add_child(indicator);
+	}
+}
+
+/// Colors turret status lights by their turret's health and state
+pub fn turret_status_system(
+	mut indicators: Query<(&Parent, &mut Sprite), With<TurretStatusIndicator>>,
+	turrets: Query<(&ship::Health, &ship::MaxHealth, &turret::TurretProperties)>,
+) {
+	for (parent, mut sprite) in indicators.iter_mut() {
+		if let Ok((health, max_health, turret_properties)) = turrets.get(parent.0) {
+			sprite.color = if turret_properties.turret_state == turret::TurretState::Disabled {
+				Color::DARK_GRAY
+			} else {
+				let health_fraction = (health.0 / max_health.0).clamp(0.0, 1.0);
+				Color::rgb(1.0 - health_fraction, health_fraction, 0.0)
+			};
+		}
+	}
+}
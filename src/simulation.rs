@@ -1,18 +1,225 @@
-use bevy::prelude::*;
+use bevy::{app::PluginGroupBuilder, core::FixedTimestep, prelude::*};
+use rand::{rngs::StdRng, SeedableRng};
 use serde::{Deserialize, Serialize};
 
 pub mod camera;
 pub mod cartridge_list;
+pub mod destruction;
+pub mod difficulty_list;
+pub mod enemy;
+pub mod enemy_list;
+pub mod escort;
+pub mod escort_list;
+pub mod faction_list;
+pub mod formation_list;
 pub mod gun;
 pub mod gun_list;
+pub mod hull;
 pub mod interaction;
+pub mod level;
+pub mod level_list;
+pub mod loadout_list;
 pub mod physics;
 pub mod projectile;
+pub mod shield;
 pub mod ship;
 pub mod ship_list;
 pub mod spawning;
+pub mod statistics;
+pub mod stronghold;
+pub mod stronghold_list;
 pub mod targeting;
 pub mod turret;
+pub mod upgrade_list;
+pub mod wave;
+pub mod wave_list;
+
+/// Length of one simulation tick in seconds.
+/// Simulation systems advance by exactly this much every time they run, regardless of frame rate.
+pub const SIMULATION_TIMESTEP: f64 = 1.0 / 60.0;
+
+/// Seed used for the simulation RNG when none is provided.
+pub const DEFAULT_SIMULATION_SEED: u64 = 0;
+
+// Stages and labels
+
+/// Fixed timestep schedule that all gameplay simulation systems run in
+#[derive(Debug, Clone, PartialEq, Eq, Hash, StageLabel)]
+pub struct SimulationStage;
+
+/// Stages inside the simulation schedule, run in order once per tick.
+/// Commands are applied at the end of each stage, so entities spawned in one stage
+/// exist for every stage after it in the same tick.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, StageLabel)]
+pub enum SimulationStep {
+	/// Spawns new ships
+	Spawn,
+	/// Consumes ship Turret Assignment Lists and spawns child turrets
+	SpawnTurrets,
+	/// Consumes turret Gun Assignment Lists and spawns child guns
+	SpawnGuns,
+	/// Runs everything else, ordered by ```SimulationSystem``` labels
+	Update,
+}
+
+/// Labels ordering the systems in ```SimulationStep::Update```.
+/// Each set runs after the one listed above it:
+/// Input -> Targeting -> Weapons -> Physics -> Collision -> Damage -> Cleanup
+#[derive(Debug, Clone, PartialEq, Eq, Hash, SystemLabel)]
+pub enum SimulationSystem {
+	/// Player orders and other external input
+	Input,
+	/// AI decisions, turret target selection and aiming
+	Targeting,
+	/// Target selection only, runs before the rest of Targeting so aiming uses this tick's targets
+	TargetSelection,
+	/// Gun cycling and projectile spawning, after turrets have aimed
+	Weapons,
+	/// Movement, after guns have fired from this tick's positions
+	Physics,
+	/// Collision detection on the moved positions, sends ```interaction::DamageEvent```s
+	Collision,
+	/// Applies damage from this tick's ```interaction::DamageEvent```s
+	Damage,
+	/// Removes anything destroyed this tick
+	Cleanup,
+}
+
+// Plugins
+
+/// Core of the simulation: sets up the fixed timestep schedule, loads definitions,
+/// and registers the resources and events shared by the other simulation plugins.
+/// Must be added before any plugin that adds simulation systems.
+#[derive(Default)]
+pub struct SimulationPlugin {
+	/// Run one simulation tick every app update instead of ticking in real time.
+	/// Lets headless runs simulate as fast as the machine allows.
+	pub tick_every_update: bool,
+}
+
+impl Plugin for SimulationPlugin {
+	fn build(&self, app: &mut App) {
+		let mut simulation_schedule = Schedule::default();
+		if !self.tick_every_update {
+			simulation_schedule =
+				simulation_schedule.with_run_criteria(FixedTimestep::step(SIMULATION_TIMESTEP));
+		}
+
+		app
+			// Load definitions
+			.insert_resource(faction_list::read_faction_definitions())
+			.insert_resource(cartridge_list::read_cartridge_definitions())
+			.insert_resource(gun_list::read_gun_definitions())
+			.insert_resource(ship_list::read_ship_definitions())
+			.insert_resource(loadout_list::read_loadout_definitions())
+			.insert_resource(upgrade_list::read_upgrade_definitions())
+			.insert_resource(enemy_list::read_enemy_definitions())
+			.insert_resource(formation_list::read_formation_definitions())
+			.insert_resource(escort_list::read_escort_group_definitions())
+			.insert_resource(wave_list::read_wave_set_definitions())
+			.insert_resource(difficulty_list::read_difficulty_curve_definitions())
+			.insert_resource(level_list::read_level_definitions())
+			.insert_resource(stronghold_list::read_stronghold_definitions())
+			// Resources
+			.init_resource::<SimulationRng>()
+			// Events
+			.add_event::<interaction::DamageEvent>()
+			.add_event::<interaction::DestroyedEvent>()
+			.add_event::<interaction::DetonationEvent>()
+			.add_event::<interaction::GameOverEvent>()
+			.add_event::<shield::ShieldDownEvent>()
+			.add_event::<hull::HullSectionDestroyedEvent>()
+			.add_event::<turret::TurretDestroyedEvent>()
+			.add_event::<gun::GunFiredEvent>()
+			.add_event::<wave::WaveStartedEvent>()
+			.add_event::<wave::WaveEndedEvent>()
+			.add_event::<level::StrongholdReachedEvent>()
+			.add_event::<stronghold::StageCompleteEvent>()
+			// Schedule
+			.add_stage_after(
+				CoreStage::Update,
+				SimulationStage,
+				simulation_schedule
+					.with_stage(SimulationStep::Spawn, SystemStage::parallel())
+					.with_stage(SimulationStep::SpawnTurrets, SystemStage::parallel())
+					.with_stage(SimulationStep::SpawnGuns, SystemStage::parallel())
+					.with_stage(SimulationStep::Update, SystemStage::parallel()),
+			)
+			// Bevy only propagates transforms once per frame, after every tick due that frame has run,
+			// so keep global transforms current wherever the simulation reads them
+			.add_simulation_system_set(
+				SimulationStep::Update,
+				SystemSet::new()
+					.before(SimulationSystem::Input)
+					.before(SimulationSystem::Targeting)
+					.with_system(bevy::transform::transform_propagate_system),
+			)
+			.add_simulation_system_set(
+				SimulationStep::Update,
+				SystemSet::new()
+					.after(SimulationSystem::Targeting)
+					.before(SimulationSystem::Weapons)
+					.with_system(bevy::transform::transform_propagate_system),
+			)
+			.add_simulation_system_set(
+				SimulationStep::Update,
+				SystemSet::new()
+					.after(SimulationSystem::Physics)
+					.before(SimulationSystem::Collision)
+					.with_system(bevy::transform::transform_propagate_system),
+			);
+	}
+}
+
+/// Every simulation plugin, everything needed to run battles without presentation
+#[derive(Default)]
+pub struct SimulationPlugins {
+	/// Passed on to ```SimulationPlugin```
+	pub tick_every_update: bool,
+}
+
+impl PluginGroup for SimulationPlugins {
+	fn build(&mut self, group: &mut PluginGroupBuilder) {
+		group
+			.add(SimulationPlugin {
+				tick_every_update: self.tick_every_update,
+			})
+			.add(spawning::SpawningPlugin)
+			.add(level::LevelPlugin)
+			.add(stronghold::StrongholdPlugin)
+			.add(enemy::EnemyAiPlugin)
+			.add(escort::EscortPlugin)
+			.add(targeting::TargetingPlugin)
+			.add(gun::WeaponsPlugin)
+			.add(physics::PhysicsPlugin)
+			.add(interaction::InteractionPlugin)
+			.add(shield::ShieldPlugin)
+			.add(hull::HullPlugin)
+			.add(turret::TurretPlugin)
+			.add(destruction::DestructionPlugin);
+	}
+}
+
+/// Adds systems to the stages of the simulation schedule
+pub trait SimulationAppExt {
+	fn add_simulation_system_set(
+		&mut self,
+		step: SimulationStep,
+		system_set: SystemSet,
+	) -> &mut Self;
+}
+
+impl SimulationAppExt for App {
+	fn add_simulation_system_set(
+		&mut self,
+		step: SimulationStep,
+		system_set: SystemSet,
+	) -> &mut Self {
+		self.stage(SimulationStage, |schedule: &mut Schedule| {
+			schedule.add_system_set_to_stage(step, system_set)
+		})
+	}
+}
 
 // Components
 
@@ -29,7 +236,50 @@ impl Default for ItemSize {
 	}
 }
 
+/// Describes how an entity should be drawn.
+/// Simulation entities only carry this, the presentation layer turns it into a sprite,
+/// so the simulation can run without rendering.
+#[derive(Component, Clone, Debug)]
+pub struct Appearance {
+	/// Texture path relative to the assets folder. None draws a plain colored rectangle.
+	pub texture_path: Option<String>,
+	/// Size to render the texture at.
+	pub size: Vec2,
+	pub color: Color,
+}
+
+impl Default for Appearance {
+	fn default() -> Self {
+		Self {
+			texture_path: None,
+			size: Vec2::new(1.0, 1.0),
+			color: Color::WHITE,
+		}
+	}
+}
+
 // Resources
 
+/// Seeded random number generator shared by all simulation systems.
+/// Anything random in the simulation must draw from this so identical seeds produce identical battles.
 #[derive(Deref, DerefMut)]
-pub struct SpawnTimer(pub Timer);
+pub struct SimulationRng(pub StdRng);
+
+impl SimulationRng {
+	pub fn from_seed(seed: u64) -> Self {
+		SimulationRng(StdRng::seed_from_u64(seed))
+	}
+}
+
+impl Default for SimulationRng {
+	fn default() -> Self {
+		SimulationRng::from_seed(DEFAULT_SIMULATION_SEED)
+	}
+}
+
+// Functions
+
+/// Returns the length of one simulation tick as a ```Duration``` for ticking timers.
+pub fn simulation_timestep_duration() -> std::time::Duration {
+	std::time::Duration::from_secs_f64(SIMULATION_TIMESTEP)
+}
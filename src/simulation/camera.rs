@@ -2,6 +2,17 @@ use bevy::render::camera::*;
 
 use super::*;
 
+/// Spawns the camera and has it follow the player ship
+pub struct CameraPlugin;
+
+impl Plugin for CameraPlugin {
+	fn build(&self, app: &mut App) {
+		app.add_startup_system(setup_camera)
+			.add_system(camera_follow_player)
+			.add_system(camera_zoom_system);
+	}
+}
+
 #[derive(Component)]
 pub struct IsCamera;
 
@@ -18,7 +29,10 @@ pub fn camera_follow_player(
 	mut camera: Query<&mut Transform, With<IsCamera>>,
 	player_ship: Query<&Transform, (With<ship::IsPlayerShip>, Without<IsCamera>)>,
 ) {
-	let camera_target = player_ship.single().translation;
+	let camera_target = match player_ship.get_single() {
+		Ok(val) => val.translation,
+		Err(_) => return, // No player ship to follow
+	};
 	let mut camera = camera
 		.get_single_mut()
 		.expect("Camera follow broke on getting the camera");
@@ -2,10 +2,14 @@ use super::*;
 
 const CARTRIDGE_DATA_PATH: &str = "data/cartridges/";
 
-#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, Debug)]
 pub enum ProjectileName {
 	TemplateProjectile,
 	SmallMachinegunBullet,
+	SmallMachinegunArmorPiercing,
+	SmallMachinegunHighExplosive,
+	SmallMachinegunFlak,
+	SmallRocket,
 }
 
 impl Default for ProjectileName {
@@ -21,10 +25,30 @@ pub struct CartridgeDefinition {
 	pub projectile_name: ProjectileName,
 	pub guidance: projectile::ProjectileGuidance,
 	pub projectile_damage: f32,
+	pub damage_type: interaction::DamageType,
+	/// Armor ignored on hitting, see ```interaction::resolve_damage```
+	pub armor_penetration: f32,
+	/// Full damage when penetrating, little when not
+	pub armor_piercing: bool,
+	/// Furthest splash damage reaches when the projectile detonates, in meters. 0 for direct hits only.
+	pub blast_radius: f32,
+	/// Fraction of the splash damage lost by the edge of the blast
+	pub blast_falloff: f32,
+	/// Detonates once a hostile ship is this close, in meters
+	pub proximity_fuse_distance: Option<f32>,
+	/// Detonates at the intercept time the firing turret predicted
+	pub timed_fuse: bool,
+	/// Health of projectiles that can be shot down, like missiles. They collide at their texture size.
+	pub projectile_health: Option<f32>,
+	/// Point defence turrets target these projectiles
+	pub targetable: bool,
 
 	pub projectile_velocity_mps: f32,
 	pub velocity_deviation_percent: f32,
 	pub bullet_spread_degrees: f32,
+	/// Furthest a shot is expected to matter, in meters.
+	/// Turrets without an intercept check their line of fire this far out.
+	pub max_range: f32,
 
 	pub texture_path: String,
 	pub texture_render_size: Vec2,
@@ -43,9 +67,19 @@ pub fn write_cartridge_definition_template() {
 		projectile_name: ProjectileName::TemplateProjectile,
 		guidance: projectile::ProjectileGuidance::None,
 		projectile_damage: 10.0,
+		damage_type: interaction::DamageType::Kinetic,
+		armor_penetration: 2.0,
+		armor_piercing: false,
+		blast_radius: 20.0,
+		blast_falloff: 0.5,
+		proximity_fuse_distance: Some(10.0),
+		timed_fuse: true,
+		projectile_health: Some(2.0),
+		targetable: true,
 		projectile_velocity_mps: 100.0,
 		velocity_deviation_percent: 0.01,
 		bullet_spread_degrees: 4.0,
+		max_range: 500.0,
 		texture_path: "template_proj_texture.png".to_string(),
 		texture_render_size: Vec2::new(1.0, 1.0),
 	};
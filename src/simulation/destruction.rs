@@ -0,0 +1,144 @@
+use super::*;
+use rand::Rng;
+
+/// Fastest debris spins, in degrees per second
+const DEBRIS_MAX_SPIN_DEGREES: f32 = 180.0;
+
+/// Debris thrown off by destroyed ships
+pub struct DestructionPlugin;
+
+impl Plugin for DestructionPlugin {
+	fn build(&self, app: &mut App) {
+		app.add_simulation_system_set(
+			SimulationStep::Update,
+			SystemSet::new()
+				.after(SimulationSystem::Cleanup)
+				.with_system(debris_spawn_system)
+				.with_system(debris_lifetime_system.after(debris_spawn_system)),
+		);
+	}
+}
+
+// ==========
+// Components
+
+/// How an entity looks and sounds when it is destroyed, and the debris it leaves behind
+#[derive(Component, Clone, Serialize, Deserialize, Debug)]
+pub struct DestructionEffect {
+	/// Explosion sprite sheet, a single row of frames, relative to textures/effects/.
+	/// None draws a plain flash instead.
+	pub explosion_texture_path: Option<String>,
+	/// Size of one frame of the sprite sheet, in pixels
+	pub explosion_frame_size: Vec2,
+	pub explosion_frames: usize,
+	pub explosion_frame_seconds: f32,
+	/// Size to render the explosion at
+	pub explosion_size: Vec2,
+	/// Relative to audio/sounds/explosions/
+	pub explosion_sound_path: Option<String>,
+	/// Pieces of debris thrown off, 0 for none
+	pub debris_count: usize,
+	/// Fastest a piece of debris flies away from the wreck, in m/s
+	pub debris_speed: f32,
+	pub debris_size: Vec2,
+	pub debris_lifetime_seconds: f32,
+}
+
+impl Default for DestructionEffect {
+	fn default() -> Self {
+		Self {
+			explosion_texture_path: None,
+			explosion_frame_size: Vec2::new(64.0, 64.0),
+			explosion_frames: 8,
+			explosion_frame_seconds: 0.05,
+			explosion_size: Vec2::new(100.0, 100.0),
+			explosion_sound_path: None,
+			debris_count: 0,
+			debris_speed: 60.0,
+			debris_size: Vec2::new(10.0, 10.0),
+			debris_lifetime_seconds: 2.0,
+		}
+	}
+}
+
+/// A piece of wreckage that drifts off and fades away
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Debris {
+	pub seconds_remaining: f32,
+	pub lifetime_seconds: f32,
+}
+
+impl Debris {
+	/// Fraction (0 to 1) of the debris' lifetime left
+	pub fn fraction_remaining(&self) -> f32 {
+		if self.lifetime_seconds <= 0.0 {
+			return 0.0;
+		}
+		return (self.seconds_remaining / self.lifetime_seconds).clamp(0.0, 1.0);
+	}
+}
+
+#[derive(Bundle)]
+pub struct DebrisBundle {
+	pub debris: Debris,
+	pub transform: Transform,
+	pub global_transform: GlobalTransform,
+	pub velocity: physics::Velocity,
+	pub velocity_rotational: physics::VelocityRotational,
+	pub appearance: Appearance,
+}
+
+// ==========
+// Systems
+
+/// Throws off the debris of everything destroyed this tick
+pub fn debris_spawn_system(
+	mut commands: Commands,
+	mut rng: ResMut<SimulationRng>,
+	mut destroyed_events: EventReader<interaction::DestroyedEvent>,
+) {
+	for destroyed_event in destroyed_events.iter() {
+		let destruction_effect = match &destroyed_event.destruction_effect {
+			Some(val) => val,
+			None => continue,
+		};
+		for _ in 0..destruction_effect.debris_count {
+			let direction =
+				Vec2::new(rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0)).normalize_or_zero();
+			let speed = destruction_effect.debris_speed * rng.gen_range(0.25..1.0);
+			let spin_degrees_per_tick = rng
+				.gen_range(-DEBRIS_MAX_SPIN_DEGREES..DEBRIS_MAX_SPIN_DEGREES)
+				* SIMULATION_TIMESTEP as f32;
+
+			commands.spawn_bundle(DebrisBundle {
+				debris: Debris {
+					seconds_remaining: destruction_effect.debris_lifetime_seconds,
+					lifetime_seconds: destruction_effect.debris_lifetime_seconds,
+				},
+				transform: Transform::from_translation(destroyed_event.translation).with_rotation(
+					Quat::from_rotation_z(rng.gen_range(0.0..std::f32::consts::TAU)),
+				),
+				global_transform: default(),
+				velocity: physics::Velocity(destroyed_event.velocity + direction * speed),
+				velocity_rotational: physics::VelocityRotational(Quat::from_rotation_z(
+					spin_degrees_per_tick.to_radians(),
+				)),
+				appearance: Appearance {
+					size: destruction_effect.debris_size,
+					color: Color::GRAY,
+					..default()
+				},
+			});
+		}
+	}
+}
+
+/// Despawns debris once it has faded out
+pub fn debris_lifetime_system(mut commands: Commands, mut debris: Query<(Entity, &mut Debris)>) {
+	for (entity, mut debris) in debris.iter_mut() {
+		debris.seconds_remaining -= SIMULATION_TIMESTEP as f32;
+		if debris.seconds_remaining <= 0.0 {
+			commands.entity(entity).despawn();
+		}
+	}
+}
@@ -0,0 +1,154 @@
+use super::*;
+
+const DIFFICULTY_DATA_PATH: &str = "data/difficulty/";
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Debug)]
+pub enum DifficultyCurveName {
+	TemplateCurve,
+	Default,
+}
+
+impl Default for DifficultyCurveName {
+	fn default() -> Self {
+		DifficultyCurveName::Default
+	}
+}
+
+/// Chance for one enemy type to be swapped for another when spawned
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct EnemySubstitutionDefinition {
+	pub from: enemy_list::EnemyName,
+	pub to: enemy_list::EnemyName,
+	/// 0 to 1
+	pub chance: f32,
+}
+
+/// Difficulty at one point along the curve
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct DifficultyPointDefinition {
+	/// Position along the curve, see ```DifficultyCurveDefinition```
+	pub progress: f32,
+	pub count_multiplier: f32,
+	pub health_multiplier: f32,
+	pub speed_multiplier: f32,
+	/// Used from this point until the next one, not blended
+	pub substitutions: Vec<EnemySubstitutionDefinition>,
+}
+
+/// Difficulty curve definition for storing how enemies escalate over a run as YAML.
+/// Progress along the curve is ```elapsed seconds * progress_per_second + meters travelled * progress_per_meter```.
+/// Multipliers are blended linearly between points, and held at the first and last points beyond them.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DifficultyCurveDefinition {
+	pub curve_name: DifficultyCurveName,
+	pub progress_per_second: f32,
+	pub progress_per_meter: f32,
+	/// Ordered by progress
+	pub points: Vec<DifficultyPointDefinition>,
+}
+
+/// Difficulty multipliers to apply to enemies at some point in a run
+#[derive(Clone, Debug)]
+pub struct DifficultyLevel {
+	pub count_multiplier: f32,
+	pub health_multiplier: f32,
+	pub speed_multiplier: f32,
+	pub substitutions: Vec<EnemySubstitutionDefinition>,
+}
+
+impl Default for DifficultyLevel {
+	fn default() -> Self {
+		Self {
+			count_multiplier: 1.0,
+			health_multiplier: 1.0,
+			speed_multiplier: 1.0,
+			substitutions: Vec::new(),
+		}
+	}
+}
+
+impl DifficultyCurveDefinition {
+	/// Returns the Difficulty Level after ```elapsed_seconds``` and ```distance_travelled``` meters
+	pub fn difficulty_at(&self, elapsed_seconds: f32, distance_travelled: f32) -> DifficultyLevel {
+		let progress = elapsed_seconds * self.progress_per_second
+			+ distance_travelled * self.progress_per_meter;
+
+		// Find the points either side of progress
+		let next_point_index = self
+			.points
+			.iter()
+			.position(|point| point.progress > progress);
+		let (previous_point, next_point) = match next_point_index {
+			None => match self.points.last() {
+				Some(last_point) => (last_point, last_point),
+				None => return DifficultyLevel::default(),
+			},
+			Some(0) => (&self.points[0], &self.points[0]),
+			Some(index) => (&self.points[index - 1], &self.points[index]),
+		};
+
+		let blend = if next_point.progress > previous_point.progress {
+			(progress - previous_point.progress) / (next_point.progress - previous_point.progress)
+		} else {
+			0.0
+		};
+		let lerp = |from: f32, to: f32| from + (to - from) * blend;
+
+		return DifficultyLevel {
+			count_multiplier: lerp(previous_point.count_multiplier, next_point.count_multiplier),
+			health_multiplier: lerp(
+				previous_point.health_multiplier,
+				next_point.health_multiplier,
+			),
+			speed_multiplier: lerp(previous_point.speed_multiplier, next_point.speed_multiplier),
+			substitutions: previous_point.substitutions.clone(),
+		};
+	}
+}
+
+/// List of Difficulty Curve Definitions
+#[derive(Deref, DerefMut, Debug)]
+pub struct DifficultyCurveDefinitionList(Vec<DifficultyCurveDefinition>);
+
+/// Generates an explanatory template for how a Difficulty Curve Definition should be formatted in YAML,
+/// then writes it out to a template.yaml file.
+pub fn write_difficulty_curve_definition_template() {
+	// Define template
+	let difficulty_curve_definition_template = DifficultyCurveDefinition {
+		curve_name: DifficultyCurveName::TemplateCurve,
+		progress_per_second: 1.0,
+		progress_per_meter: 0.01,
+		points: vec![
+			DifficultyPointDefinition {
+				progress: 0.0,
+				count_multiplier: 1.0,
+				health_multiplier: 1.0,
+				speed_multiplier: 1.0,
+				substitutions: vec![],
+			},
+			DifficultyPointDefinition {
+				progress: 300.0,
+				count_multiplier: 2.0,
+				health_multiplier: 1.5,
+				speed_multiplier: 1.2,
+				substitutions: vec![EnemySubstitutionDefinition {
+					from: enemy_list::EnemyName::TemplateEnemy,
+					to: enemy_list::EnemyName::TemplateEnemy,
+					chance: 0.25,
+				}],
+			},
+		],
+	};
+	// Write out template
+	crate::game_io::write_definition_template(
+		DIFFICULTY_DATA_PATH,
+		difficulty_curve_definition_template,
+	);
+}
+
+/// Reads all *.yaml Difficulty Curve definition files in data/difficulty/ and returns them as a ```DifficultyCurveDefinitionList```
+pub fn read_difficulty_curve_definitions() -> DifficultyCurveDefinitionList {
+	let difficulty_curve_definition_list =
+		DifficultyCurveDefinitionList(crate::game_io::read_definitions(DIFFICULTY_DATA_PATH));
+	return difficulty_curve_definition_list;
+}
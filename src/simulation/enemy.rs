@@ -0,0 +1,235 @@
+use super::{faction_list::FactionName, physics::*, *};
+use bevy::sprite::collide_aabb::collide;
+
+/// Seconds a steering enemy takes to reach its desired velocity, before MaxAcceleration limits
+pub const STEERING_RESPONSE_SECONDS: f32 = 0.5;
+/// How far either side of its range a Standoff enemy eases off, in meters
+const STANDOFF_TOLERANCE: f32 = 100.0;
+/// Longest lead, in seconds, pursuing enemies aim ahead of the player ship
+const PURSUIT_MAX_LEAD_SECONDS: f32 = 3.0;
+/// Speed, per meter out of position, formation members close on their slot with
+const FORMATION_GAIN: f32 = 1.0;
+
+/// Enemy steering and ramming
+pub struct EnemyAiPlugin;
+
+impl Plugin for EnemyAiPlugin {
+	fn build(&self, app: &mut App) {
+		app.add_simulation_system_set(
+			SimulationStep::Update,
+			SystemSet::new()
+				.label(SimulationSystem::Targeting)
+				.with_system(enemy_steering_system.before(SimulationSystem::TargetSelection)),
+		)
+		.add_simulation_system_set(
+			SimulationStep::Update,
+			SystemSet::new()
+				.label(SimulationSystem::Collision)
+				.after(SimulationSystem::Physics)
+				.with_system(ram_collision_system.after(projectile_collision_system)),
+		);
+	}
+}
+
+/// How an enemy moves, relative to the player ship
+#[derive(Component, Clone, Copy, PartialEq, Serialize, Deserialize, Debug)]
+pub enum EnemyBehaviour {
+	/// Keeps the velocity it spawned with
+	Drift,
+	/// Heads for where the player ship is going to be
+	Pursue,
+	/// Closes to ```range``` meters from the player ship and holds there
+	Standoff { range: f32 },
+	/// Circles the player ship at ```radius``` meters
+	Orbit { radius: f32, clockwise: bool },
+	/// Pursues the player ship at full speed, and destroys itself doing ```ram_damage``` to the first hostile ship it hits
+	Kamikaze { ram_damage: f32 },
+}
+
+impl Default for EnemyBehaviour {
+	fn default() -> Self {
+		EnemyBehaviour::Drift
+	}
+}
+
+/// Health fraction below which the enemy flees from the player ship, overriding its behaviour
+#[derive(Component, Clone, Copy, Deref, DerefMut, Default, Debug)]
+pub struct FleeWhenDamaged(pub Option<f32>);
+
+/// Keeps the enemy at ```offset``` from its formation ```leader```, overriding its behaviour while the leader lives
+#[derive(Component, Clone, Copy, Debug)]
+pub struct FormationMember {
+	pub leader: Entity,
+	pub offset: Vec2,
+}
+
+/// Returns the velocity an enemy at ```position``` wants, to carry out ```behaviour``` against the player ship
+pub fn behaviour_desired_velocity(
+	behaviour: EnemyBehaviour,
+	position: Vec2,
+	velocity: Vec2,
+	max_speed: f32,
+	player_position: Vec2,
+	player_velocity: Vec2,
+) -> Vec2 {
+	let to_player = player_position - position;
+	let player_distance = to_player.length();
+	let player_direction = to_player.normalize_or_zero();
+
+	match behaviour {
+		EnemyBehaviour::Drift => velocity,
+		EnemyBehaviour::Pursue | EnemyBehaviour::Kamikaze { .. } => {
+			let lead_seconds = if max_speed > 0.0 {
+				(player_distance / max_speed).min(PURSUIT_MAX_LEAD_SECONDS)
+			} else {
+				0.0
+			};
+			(to_player + player_velocity * lead_seconds).normalize_or_zero() * max_speed
+		}
+		EnemyBehaviour::Standoff { range } => {
+			let approach = ((player_distance - range) / STANDOFF_TOLERANCE).clamp(-1.0, 1.0);
+			player_velocity + player_direction * approach * max_speed
+		}
+		EnemyBehaviour::Orbit { radius, clockwise } => {
+			let tangent = if clockwise {
+				player_direction.perp()
+			} else {
+				-player_direction.perp()
+			};
+			let approach = ((player_distance - radius) / radius.max(1.0)).clamp(-1.0, 1.0);
+			player_velocity
+				+ (tangent + player_direction * approach).normalize_or_zero() * max_speed
+		}
+	}
+}
+
+// ==========
+// Systems
+
+/// Steers enemies by setting their Acceleration towards the velocity their behaviour wants
+pub fn enemy_steering_system(
+	mut enemies: Query<
+		(
+			&mut Acceleration,
+			&Velocity,
+			&Transform,
+			&MaxSpeed,
+			&MaxAcceleration,
+			&EnemyBehaviour,
+			&FleeWhenDamaged,
+			&ship::Health,
+			&ship::MaxHealth,
+			Option<&FormationMember>,
+		),
+		With<ship::IsEnemyShip>,
+	>,
+	leaders: Query<(&Transform, &Velocity), With<ship::IsEnemyShip>>,
+	player_ship: Query<(&Transform, &Velocity), With<ship::IsPlayerShip>>,
+) {
+	let (player_position, player_velocity) = match player_ship.get_single() {
+		Ok((transform, velocity)) => (transform.translation.truncate(), velocity.0),
+		Err(_) => (Vec2::ZERO, Vec2::ZERO), // No player, head for the origin
+	};
+
+	for (
+		mut acceleration,
+		velocity,
+		transform,
+		max_speed,
+		max_acceleration,
+		behaviour,
+		flee_when_damaged,
+		health,
+		max_health,
+		formation_member,
+	) in enemies.iter_mut()
+	{
+		let position = transform.translation.truncate();
+
+		let fleeing = match flee_when_damaged.0 {
+			Some(health_fraction) => health.0 < max_health.0 * health_fraction,
+			None => false,
+		};
+		let formation_leader = formation_member.and_then(|formation_member| {
+			leaders
+				.get(formation_member.leader)
+				.ok()
+				.map(|leader| (formation_member, leader))
+		});
+
+		let desired_velocity = if fleeing {
+			(position - player_position).normalize_or_zero() * max_speed.0
+		} else if let Some((formation_member, (leader_transform, leader_velocity))) =
+			formation_leader
+		{
+			let slot = leader_transform.translation.truncate() + formation_member.offset;
+			(leader_velocity.0 + (slot - position) * FORMATION_GAIN).clamp_length_max(max_speed.0)
+		} else {
+			behaviour_desired_velocity(
+				*behaviour,
+				position,
+				velocity.0,
+				max_speed.0,
+				player_position,
+				player_velocity,
+			)
+		};
+
+		acceleration.0 = ((desired_velocity - velocity.0) / STEERING_RESPONSE_SECONDS)
+			.clamp_length_max(max_acceleration.0);
+	}
+}
+
+/// Kamikaze enemies that touch a hostile ship damage it and destroy themselves
+pub fn ram_collision_system(
+	mut damage_events: EventWriter<interaction::DamageEvent>,
+	faction_definition_list: Res<faction_list::FactionDefinitionList>,
+	mut rammers: Query<(
+		&EnemyBehaviour,
+		&Transform,
+		&Collider,
+		&FactionName,
+		&mut ship::Health,
+	)>,
+	targets: Query<
+		(Entity, &Transform, &Collider, &FactionName),
+		(With<ship::Health>, Without<projectile::IsProjectile>),
+	>,
+) {
+	for (behaviour, rammer_transform, rammer_collider, rammer_faction, mut rammer_health) in
+		rammers.iter_mut()
+	{
+		// Rams hit like a warhead going off against the hull
+		let ram_damage = match behaviour {
+			EnemyBehaviour::Kamikaze { ram_damage } => interaction::Damage {
+				amount: *ram_damage,
+				damage_type: interaction::DamageType::Explosive,
+				..default()
+			},
+			_ => continue,
+		};
+		for (target_entity, target_transform, target_collider, target_faction) in targets.iter() {
+			if !faction_definition_list.is_hostile(*rammer_faction, *target_faction) {
+				continue;
+			}
+			let collision = collide(
+				target_transform.translation,
+				target_collider.0,
+				rammer_transform.translation,
+				rammer_collider.0,
+			);
+			if collision.is_some() {
+				damage_events.send(interaction::DamageEvent {
+					target: target_entity,
+					damage: ram_damage,
+					source: None,
+					impact: Some(rammer_transform.translation.truncate()),
+					direct_hit: true,
+				});
+				// Rammer is spent, whatever its own shields and armor
+				rammer_health.0 = 0.0;
+				break;
+			}
+		}
+	}
+}
@@ -0,0 +1,69 @@
+use super::*;
+
+const ENEMY_DATA_PATH: &str = "data/enemies/";
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Debug)]
+pub enum EnemyName {
+	TemplateEnemy,
+	Drone,
+	Gunship,
+}
+
+/// Enemy definition for storing enemy parameters as YAML.
+/// The hull (texture, collider and turret mounts) comes from the Ship Definition named by ```ship_name```.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct EnemyDefinition {
+	pub enemy_name: EnemyName,
+	pub ship_name: ship_list::ShipName,
+	/// Replaces the faction of the hull
+	pub faction: faction_list::FactionName,
+	/// Replaces the health of the hull
+	pub health: ship::Health,
+	/// Replaces the max speed of the hull. None keeps the hull's.
+	pub speed: Option<physics::MaxSpeed>,
+	pub acceleration: physics::MaxAcceleration,
+	pub behaviour: enemy::EnemyBehaviour,
+	/// Health fraction (0 to 1) below which the enemy gives up and flees from the player. None never flees.
+	pub flee_health_fraction: Option<f32>,
+	pub turrets: Vec<loadout_list::TurretLoadoutDefinition>,
+}
+
+/// List of Enemy Definitions
+#[derive(Deref, DerefMut, Debug)]
+pub struct EnemyDefinitionList(Vec<EnemyDefinition>);
+
+/// Generates an explanatory template for how an Enemy Definition should be formatted in YAML,
+/// then writes it out to a template.yaml file.
+pub fn write_enemy_definition_template() {
+	// Define template
+	let enemy_definition_template = EnemyDefinition {
+		enemy_name: EnemyName::TemplateEnemy,
+		ship_name: ship_list::ShipName::TemplateShip,
+		faction: faction_list::FactionName::Raiders,
+		health: ship::Health(50.0),
+		// Optional, leave it out to fly at the hull's max speed
+		speed: Some(physics::MaxSpeed(50.0)),
+		acceleration: physics::MaxAcceleration(25.0),
+		behaviour: enemy::EnemyBehaviour::Orbit {
+			radius: 300.0,
+			clockwise: true,
+		},
+		flee_health_fraction: Some(0.25),
+		turrets: vec![loadout_list::TurretLoadoutDefinition {
+			mount_number: 0,
+			gun_name: gun_list::GunName::TemplateGun,
+			num_barrels: turret::TurretNumBarrels::Single,
+			ammunition: None,
+			role: turret::TurretRole::General,
+		}],
+	};
+	// Write out template
+	crate::game_io::write_definition_template(ENEMY_DATA_PATH, enemy_definition_template);
+}
+
+/// Reads all *.yaml Enemy definition files in data/enemies/ and returns them as an ```EnemyDefinitionList```
+pub fn read_enemy_definitions() -> EnemyDefinitionList {
+	let enemy_definition_list =
+		EnemyDefinitionList(crate::game_io::read_definitions(ENEMY_DATA_PATH));
+	return enemy_definition_list;
+}
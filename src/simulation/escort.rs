@@ -0,0 +1,142 @@
+use super::{physics::*, *};
+
+/// How close to the player ship guarding escorts keep, in meters
+const GUARD_DISTANCE: f32 = 120.0;
+/// Range attacking escorts hold from their target, in meters
+const ATTACK_RANGE: f32 = 300.0;
+/// Speed, per meter out of position, escorts close on their station with
+const STATION_GAIN: f32 = 1.0;
+
+/// Allied escort ships that accompany the player ship and follow its orders
+pub struct EscortPlugin;
+
+impl Plugin for EscortPlugin {
+	fn build(&self, app: &mut App) {
+		app.add_simulation_system_set(
+			SimulationStep::Update,
+			SystemSet::new()
+				.label(SimulationSystem::Targeting)
+				.with_system(escort_order_system.before(SimulationSystem::TargetSelection))
+				.with_system(
+					escort_steering_system
+						.after(escort_order_system)
+						.before(SimulationSystem::TargetSelection),
+				),
+		);
+	}
+}
+
+// ==========
+// Components
+
+#[derive(Component, Default)]
+pub struct IsEscortShip;
+
+/// Where the escort keeps station relative to the player ship: +x to its right, +y ahead of it
+#[derive(Component, Clone, Copy, Deref, DerefMut, Default, Debug)]
+pub struct EscortStation(pub Vec2);
+
+/// What the player has told the escort to do
+#[derive(Component, Clone, Copy, PartialEq, Debug)]
+pub enum EscortOrder {
+	/// Hold station on the player ship, engaging whatever comes in range
+	Screen,
+	/// Close in tight around the player ship
+	Guard,
+	/// Leave station to engage the target, returning to Screen once it is destroyed
+	AttackTarget(Entity),
+}
+
+impl Default for EscortOrder {
+	fn default() -> Self {
+		EscortOrder::Screen
+	}
+}
+
+// ==========
+// Systems
+
+/// Points escorts' turrets at their ordered target, and stands them down once it is gone
+pub fn escort_order_system(
+	mut escorts: Query<(&mut EscortOrder, &mut targeting::PriorityTarget), With<IsEscortShip>>,
+	targets: Query<Entity, (With<ship::Health>, Without<ship::IsDestroyed>)>,
+) {
+	for (mut escort_order, mut priority_target) in escorts.iter_mut() {
+		if let EscortOrder::AttackTarget(target) = *escort_order {
+			if targets.get(target).is_err() {
+				*escort_order = EscortOrder::Screen;
+			}
+		}
+		priority_target.0 = match *escort_order {
+			EscortOrder::AttackTarget(target) => Some(target),
+			_ => None,
+		};
+	}
+}
+
+/// Steers escorts by setting their Acceleration towards the velocity their order wants
+pub fn escort_steering_system(
+	mut escorts: Query<
+		(
+			&mut Acceleration,
+			&Velocity,
+			&Transform,
+			&MaxSpeed,
+			&MaxAcceleration,
+			&EscortStation,
+			&EscortOrder,
+		),
+		With<IsEscortShip>,
+	>,
+	targets: Query<(&Transform, &Velocity), Without<IsEscortShip>>,
+	player_ship: Query<(&Transform, &Velocity), With<ship::IsPlayerShip>>,
+) {
+	let (player_transform, player_velocity) = match player_ship.get_single() {
+		Ok(val) => val,
+		Err(_) => return, // Nobody to escort, hold course
+	};
+	let player_position = player_transform.translation.truncate();
+
+	for (
+		mut acceleration,
+		velocity,
+		transform,
+		max_speed,
+		max_acceleration,
+		station,
+		escort_order,
+	) in escorts.iter_mut()
+	{
+		let position = transform.translation.truncate();
+		// Stations turn with the player ship
+		let station_offset = (player_transform.rotation * station.extend(0.0)).truncate();
+
+		let desired_velocity = match *escort_order {
+			EscortOrder::Screen | EscortOrder::Guard => {
+				let station_offset = if *escort_order == EscortOrder::Guard {
+					station_offset.normalize_or_zero() * GUARD_DISTANCE
+				} else {
+					station_offset
+				};
+				let slot = player_position + station_offset;
+				(player_velocity.0 + (slot - position) * STATION_GAIN).clamp_length_max(max_speed.0)
+			}
+			EscortOrder::AttackTarget(target) => match targets.get(target) {
+				Ok((target_transform, target_velocity)) => enemy::behaviour_desired_velocity(
+					enemy::EnemyBehaviour::Standoff {
+						range: ATTACK_RANGE,
+					},
+					position,
+					velocity.0,
+					max_speed.0,
+					target_transform.translation.truncate(),
+					target_velocity.0,
+				),
+				Err(_) => player_velocity.0,
+			},
+		};
+
+		acceleration.0 = ((desired_velocity - velocity.0) / enemy::STEERING_RESPONSE_SECONDS)
+			.clamp_length_max(max_acceleration.0);
+	}
+}
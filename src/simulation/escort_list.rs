@@ -0,0 +1,68 @@
+use super::*;
+
+const ESCORT_DATA_PATH: &str = "data/escorts/";
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Debug)]
+pub enum EscortGroupName {
+	TemplateEscortGroup,
+	Default,
+	/// No escorts at all
+	Unescorted,
+}
+
+impl Default for EscortGroupName {
+	fn default() -> Self {
+		EscortGroupName::Default
+	}
+}
+
+/// One escort ship and where it keeps station on the player ship
+#[derive(Serialize, Deserialize, Debug)]
+pub struct EscortDefinition {
+	/// Hull and turrets of the escort
+	pub loadout_name: loadout_list::LoadoutName,
+	pub acceleration: physics::MaxAcceleration,
+	/// Station relative to the player ship: +x to its right, +y ahead of it
+	pub station: Vec2,
+}
+
+/// Escort group definition for storing the allied ships that accompany the player as YAML
+#[derive(Serialize, Deserialize, Debug)]
+pub struct EscortGroupDefinition {
+	pub escort_group_name: EscortGroupName,
+	pub escorts: Vec<EscortDefinition>,
+}
+
+/// List of Escort Group Definitions
+#[derive(Deref, DerefMut, Debug)]
+pub struct EscortGroupDefinitionList(Vec<EscortGroupDefinition>);
+
+/// Generates an explanatory template for how an Escort Group Definition should be formatted in YAML,
+/// then writes it out to a template.yaml file.
+pub fn write_escort_group_definition_template() {
+	// Define template
+	let escort_group_definition_template = EscortGroupDefinition {
+		escort_group_name: EscortGroupName::TemplateEscortGroup,
+		escorts: vec![
+			EscortDefinition {
+				loadout_name: loadout_list::LoadoutName::TemplateLoadout,
+				acceleration: physics::MaxAcceleration(60.0),
+				station: Vec2::new(-200.0, 50.0),
+			},
+			EscortDefinition {
+				loadout_name: loadout_list::LoadoutName::TemplateLoadout,
+				acceleration: physics::MaxAcceleration(60.0),
+				station: Vec2::new(200.0, 50.0),
+			},
+		],
+	};
+	// Write out template
+	crate::game_io::write_definition_template(ESCORT_DATA_PATH, escort_group_definition_template);
+}
+
+/// Reads all *.yaml Escort Group definition files in data/escorts/ and returns them as an ```EscortGroupDefinitionList```
+pub fn read_escort_group_definitions() -> EscortGroupDefinitionList {
+	let escort_group_definition_list =
+		EscortGroupDefinitionList(crate::game_io::read_definitions(ESCORT_DATA_PATH));
+	return escort_group_definition_list;
+}
@@ -0,0 +1,189 @@
+use super::*;
+
+const FACTION_DATA_PATH: &str = "data/factions/";
+
+/// Faction the player and their escorts fly for
+pub const PLAYER_FACTION: FactionName = FactionName::Player;
+
+/// Side an entity fights for. Who it shoots at and who its projectiles hit comes from the Faction Definitions.
+#[derive(Component, Clone, Copy, PartialEq, Serialize, Deserialize, Debug)]
+pub enum FactionName {
+	TemplateFaction,
+	Neutral,
+	Player,
+	Raiders,
+	Garrison,
+}
+
+impl Default for FactionName {
+	fn default() -> Self {
+		FactionName::Neutral
+	}
+}
+
+/// How one faction treats another.
+/// Hostile factions target and hit each other, allied factions never do,
+/// and neutral factions don't target each other but can still be hit by stray fire.
+/// Ordered from least to most hostile.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Debug)]
+pub enum Relationship {
+	Allied,
+	Neutral,
+	Hostile,
+}
+
+/// Relationship Definition for storing a faction's stance towards another in Faction Definitions
+#[derive(Clone, Copy, Serialize, Deserialize, Debug)]
+pub struct FactionRelationshipDefinition {
+	pub faction_name: FactionName,
+	pub relationship: Relationship,
+}
+
+/// Faction definition for storing a faction's relationships as YAML.
+/// Relationships only need listing on one of the two factions. Listed on both, the more hostile one holds.
+/// Unlisted factions are Neutral, except the faction itself, which is Allied unless listed.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct FactionDefinition {
+	pub faction_name: FactionName,
+	pub relationships: Vec<FactionRelationshipDefinition>,
+}
+
+/// List of Faction Definitions
+#[derive(Deref, DerefMut, Debug)]
+pub struct FactionDefinitionList(Vec<FactionDefinition>);
+
+impl FactionDefinitionList {
+	/// Relationship between ```faction``` and ```other```, looked up in both factions' definitions.
+	/// The same both ways round: if the two list each other differently, the more hostile one holds.
+	pub fn relationship(&self, faction: FactionName, other: FactionName) -> Relationship {
+		let listed_relationship = self
+			.listed_relationship(faction, other)
+			.max(self.listed_relationship(other, faction));
+		if let Some(relationship) = listed_relationship {
+			return relationship;
+		}
+		if faction == other {
+			return Relationship::Allied;
+		}
+		return Relationship::Neutral;
+	}
+
+	pub fn is_hostile(&self, faction: FactionName, other: FactionName) -> bool {
+		self.relationship(faction, other) == Relationship::Hostile
+	}
+
+	pub fn is_allied(&self, faction: FactionName, other: FactionName) -> bool {
+		self.relationship(faction, other) == Relationship::Allied
+	}
+
+	fn listed_relationship(
+		&self,
+		faction: FactionName,
+		other: FactionName,
+	) -> Option<Relationship> {
+		self.iter()
+			.find(|faction_definition| faction_definition.faction_name == faction)?
+			.relationships
+			.iter()
+			.find(|relationship_definition| relationship_definition.faction_name == other)
+			.map(|relationship_definition| relationship_definition.relationship)
+	}
+}
+
+/// Generates an explanatory template for how a Faction Definition should be formatted in YAML,
+/// then writes it out to a template.yaml file.
+pub fn write_faction_definition_template() {
+	// Define template
+	let faction_definition_template = FactionDefinition {
+		faction_name: FactionName::TemplateFaction,
+		relationships: vec![
+			FactionRelationshipDefinition {
+				faction_name: FactionName::Player,
+				relationship: Relationship::Hostile,
+			},
+			FactionRelationshipDefinition {
+				faction_name: FactionName::Raiders,
+				relationship: Relationship::Allied,
+			},
+		],
+	};
+	// Write out template
+	crate::game_io::write_definition_template(FACTION_DATA_PATH, faction_definition_template);
+}
+
+/// Reads all *.yaml Faction definition files in data/factions/ and returns them as a ```FactionDefinitionList```
+pub fn read_faction_definitions() -> FactionDefinitionList {
+	let faction_definition_list =
+		FactionDefinitionList(crate::game_io::read_definitions(FACTION_DATA_PATH));
+	return faction_definition_list;
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn faction_definition(
+		faction_name: FactionName,
+		relationships: &[(FactionName, Relationship)],
+	) -> FactionDefinition {
+		return FactionDefinition {
+			faction_name,
+			relationships: relationships
+				.iter()
+				.map(
+					|&(faction_name, relationship)| FactionRelationshipDefinition {
+						faction_name,
+						relationship,
+					},
+				)
+				.collect(),
+		};
+	}
+
+	#[test]
+	fn relationships_listed_once_hold_both_ways() {
+		let faction_definition_list = FactionDefinitionList(vec![
+			faction_definition(
+				FactionName::Player,
+				&[(FactionName::Raiders, Relationship::Hostile)],
+			),
+			faction_definition(FactionName::Raiders, &[]),
+		]);
+		assert!(faction_definition_list.is_hostile(FactionName::Player, FactionName::Raiders));
+		assert!(faction_definition_list.is_hostile(FactionName::Raiders, FactionName::Player));
+		assert!(faction_definition_list.is_allied(FactionName::Raiders, FactionName::Raiders));
+		assert_eq!(
+			faction_definition_list.relationship(FactionName::Player, FactionName::Garrison),
+			Relationship::Neutral
+		);
+	}
+
+	#[test]
+	fn conflicting_relationships_take_the_more_hostile() {
+		let faction_definition_list = FactionDefinitionList(vec![
+			faction_definition(
+				FactionName::Player,
+				&[(FactionName::Raiders, Relationship::Allied)],
+			),
+			faction_definition(
+				FactionName::Raiders,
+				&[(FactionName::Player, Relationship::Hostile)],
+			),
+			faction_definition(
+				FactionName::Garrison,
+				&[(FactionName::Player, Relationship::Allied)],
+			),
+			faction_definition(
+				FactionName::Neutral,
+				&[(FactionName::Garrison, Relationship::Neutral)],
+			),
+		]);
+		assert!(faction_definition_list.is_hostile(FactionName::Player, FactionName::Raiders));
+		assert!(faction_definition_list.is_hostile(FactionName::Raiders, FactionName::Player));
+		assert!(faction_definition_list.is_allied(FactionName::Player, FactionName::Garrison));
+		assert_eq!(
+			faction_definition_list.relationship(FactionName::Garrison, FactionName::Neutral),
+			Relationship::Neutral
+		);
+	}
+}
@@ -0,0 +1,135 @@
+use super::*;
+
+const FORMATION_DATA_PATH: &str = "data/formations/";
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Debug)]
+pub enum FormationName {
+	TemplateFormation,
+	LineAbreast,
+	Vee,
+	Column,
+	Ring,
+	Stream,
+}
+
+/// Shape of a formation.
+/// Slots are relative to the leader in slot 0: +x to the leader's right, +y ahead of the leader.
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+pub enum FormationShape {
+	/// Side by side, ```spacing``` meters apart, filling out alternately to either side of the leader
+	LineAbreast { spacing: f32 },
+	/// Trailing back from the leader at the point, arms swept back ```sweep_degrees``` from abeam
+	Vee { spacing: f32, sweep_degrees: f32 },
+	/// Single file behind the leader
+	Column { spacing: f32 },
+	/// Evenly spaced around the player ship, ```radius``` meters out, closing in.
+	/// Placed around the player rather than a leader, so never kept.
+	Ring { radius: f32 },
+	/// Single file behind the leader, weaving ```stagger``` meters to alternate sides
+	Stream { spacing: f32, stagger: f32 },
+	/// Hand placed slots. More enemies than slots repeat the pattern further back,
+	/// ```repeat_spacing``` meters behind the rearmost slot.
+	Slots {
+		slots: Vec<Vec2>,
+		repeat_spacing: f32,
+	},
+}
+
+impl FormationShape {
+	/// Returns the slot of each of ```count``` enemies, relative to the leader in slot 0
+	pub fn slot_offsets(&self, count: usize) -> Vec<Vec2> {
+		let mut slot_offsets = Vec::new();
+		for slot_number in 0..count {
+			// Which side of the leader, and how many places out from them
+			let side = if slot_number % 2 == 1 { -1.0 } else { 1.0 };
+			let rank = slot_number.div_ceil(2) as f32;
+
+			let slot_offset = match self {
+				FormationShape::LineAbreast { spacing } => Vec2::new(side * rank * spacing, 0.0),
+				FormationShape::Vee {
+					spacing,
+					sweep_degrees,
+				} => {
+					let (sweep_sin, sweep_cos) = sweep_degrees.to_radians().sin_cos();
+					Vec2::new(
+						side * rank * spacing * sweep_cos,
+						-rank * spacing * sweep_sin,
+					)
+				}
+				FormationShape::Column { spacing } => {
+					Vec2::new(0.0, -(slot_number as f32) * spacing)
+				}
+				FormationShape::Ring { radius } => {
+					let angle = std::f32::consts::TAU * slot_number as f32 / count as f32;
+					Vec2::new(angle.cos(), angle.sin()) * *radius
+				}
+				FormationShape::Stream { spacing, stagger } => {
+					let weave = if slot_number == 0 {
+						0.0
+					} else {
+						side * stagger
+					};
+					Vec2::new(weave, -(slot_number as f32) * spacing)
+				}
+				FormationShape::Slots {
+					slots,
+					repeat_spacing,
+				} => {
+					if slots.is_empty() {
+						Vec2::ZERO
+					} else {
+						let depth = slots.iter().map(|slot| slot.y).fold(0.0, f32::max)
+							- slots.iter().map(|slot| slot.y).fold(0.0, f32::min);
+						let repeats = (slot_number / slots.len()) as f32;
+						slots[slot_number % slots.len()]
+							- Vec2::new(0.0, repeats * (depth + repeat_spacing))
+					}
+				}
+			};
+			slot_offsets.push(slot_offset);
+		}
+		return slot_offsets;
+	}
+}
+
+/// Formation definition for storing the shape enemies arrive in as YAML
+#[derive(Serialize, Deserialize, Debug)]
+pub struct FormationDefinition {
+	pub formation_name: FormationName,
+	pub shape: FormationShape,
+	/// Whether the rest of the formation keeps station on the leader after spawning,
+	/// rather than each going about its own behaviour
+	pub keep_formation: bool,
+}
+
+/// List of Formation Definitions
+#[derive(Deref, DerefMut, Debug)]
+pub struct FormationDefinitionList(Vec<FormationDefinition>);
+
+/// Generates an explanatory template for how a Formation Definition should be formatted in YAML,
+/// then writes it out to a template.yaml file.
+pub fn write_formation_definition_template() {
+	// Define template
+	let formation_definition_template = FormationDefinition {
+		formation_name: FormationName::TemplateFormation,
+		shape: FormationShape::Slots {
+			slots: vec![
+				Vec2::new(0.0, 0.0),
+				Vec2::new(-60.0, -40.0),
+				Vec2::new(60.0, -40.0),
+				Vec2::new(0.0, -80.0),
+			],
+			repeat_spacing: 60.0,
+		},
+		keep_formation: true,
+	};
+	// Write out template
+	crate::game_io::write_definition_template(FORMATION_DATA_PATH, formation_definition_template);
+}
+
+/// Reads all *.yaml Formation definition files in data/formations/ and returns them as a ```FormationDefinitionList```
+pub fn read_formation_definitions() -> FormationDefinitionList {
+	let formation_definition_list =
+		FormationDefinitionList(crate::game_io::read_definitions(FORMATION_DATA_PATH));
+	return formation_definition_list;
+}
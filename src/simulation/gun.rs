@@ -1,6 +1,24 @@
 use super::*;
+use rand::Rng;
 
 const BASE_TEXTURE_PATH_PROJECTILES: &str = "textures/projectiles/";
+/// Fraction of its ship's collider, around the middle, that blocks the ship's own turrets from firing across it
+const HULL_CORE_FRACTION: f32 = 0.5;
+
+/// Gun cycling and firing
+pub struct WeaponsPlugin;
+
+impl Plugin for WeaponsPlugin {
+	fn build(&self, app: &mut App) {
+		app.add_simulation_system_set(
+			SimulationStep::Update,
+			SystemSet::new()
+				.label(SimulationSystem::Weapons)
+				.after(SimulationSystem::Targeting)
+				.with_system(gun_firing_system),
+		);
+	}
+}
 
 #[derive(Clone, Copy, Serialize, Deserialize, Debug)]
 pub enum GunType {
@@ -25,11 +43,13 @@ pub struct GunCycleTimer(Timer);
 /// Weapon properties of a gun and data needed for its operation and projectile spawning
 #[derive(Component, Clone, Default, Debug)]
 pub struct GunProperties {
+	pub gun_name: gun_list::GunName,
 	pub gun_type: GunType,
 	pub gun_size: ItemSize,
 	pub rate_of_fire: f32,
 	pub cartridge_data: cartridge_list::CartridgeDefinition,
-	pub fire_sound: Handle<AudioSource>,
+	/// Path to the sound played when the gun fires, relative to the assets folder
+	pub fire_sound_path: String,
 }
 
 /// Bundle of components needed to spawn a gun
@@ -41,9 +61,7 @@ pub struct GunBundle {
 
 	pub gun_cycle_timer: GunCycleTimer,
 
-	pub texture: Handle<Image>,
-	pub sprite: Sprite,
-	pub visibility: Visibility,
+	pub appearance: Appearance,
 }
 
 impl Default for GunBundle {
@@ -55,45 +73,89 @@ impl Default for GunBundle {
 
 			gun_cycle_timer: GunCycleTimer(Timer::default()),
 
-			texture: default(),
-			sprite: default(),
-			visibility: default(),
+			appearance: default(),
 		}
 	}
 }
 
+// ==========
+// Events
+
+/// Sent every time a gun fires a shot
+#[derive(Debug)]
+pub struct GunFiredEvent {
+	pub gun: Entity,
+	pub turret: Entity,
+	pub gun_name: gun_list::GunName,
+}
+
 // ==========
 // Systems
 
+#[allow(clippy::collapsible_if)]
 pub fn gun_firing_system(
-	time: Res<Time>,
-	audio: Res<Audio>,
-	asset_server: Res<AssetServer>,
+	mut rng: ResMut<SimulationRng>,
 	mut commands: Commands,
-	mut guns: Query<(&Parent, &GlobalTransform, &mut GunCycleTimer)>,
+	mut gun_fired_events: EventWriter<GunFiredEvent>,
+	faction_definition_list: Res<faction_list::FactionDefinitionList>,
+	mut guns: Query<(Entity, &Parent, &GlobalTransform, &mut GunCycleTimer)>,
 	turrets: Query<(&Parent, &turret::TurretProperties, &GunProperties)>,
-	ships: Query<(&interaction::IFF, &physics::Velocity)>,
+	ships: Query<(
+		&faction_list::FactionName,
+		&physics::Velocity,
+		&Transform,
+		&physics::Collider,
+	)>,
+	friendly_ships: Query<
+		(
+			Entity,
+			&Transform,
+			&physics::Collider,
+			&faction_list::FactionName,
+		),
+		(With<ship::Health>, Without<projectile::IsProjectile>),
+	>,
 ) {
-	for (parent_turret, gun_transform, mut gun_cycle_timer) in guns.iter_mut() {
+	for (gun_entity, parent_turret, gun_transform, mut gun_cycle_timer) in guns.iter_mut() {
 		// Get Turret and Gun properties from parent turret
 		let (turret_parent, turret_properties, gun_properties) = turrets
 			.get(parent_turret.0)
 			.expect("Failed to get parent turret.");
 
-		gun_cycle_timer.tick(time.delta());
+		gun_cycle_timer.tick(simulation_timestep_duration());
 
-		let (ship_iff, ship_velocity) = ships
+		let (ship_faction, ship_velocity, ship_transform, ship_collider) = ships
 			.get(turret_parent.0)
 			.expect("Failed to get turret's parent ship");
 
 		if turret_properties.turret_state == turret::TurretState::Firing {
 			if gun_cycle_timer.finished() {
+				// Hold fire until the shot won't cross the ship's own hull or a ship that isn't hostile
+				let shot_distance = match turret_properties.intercept_seconds {
+					Some(intercept_seconds) => {
+						intercept_seconds * gun_properties.cartridge_data.projectile_velocity_mps
+					}
+					// No intercept to stop at, so check as far as the shot can matter
+					None => gun_properties.cartridge_data.max_range,
+				};
+				let line_of_fire_clear = line_of_fire_clear(
+					gun_transform.translation.truncate(),
+					(gun_transform.rotation * Vec3::Y).truncate(),
+					shot_distance,
+					(turret_parent.0, ship_faction, ship_transform, ship_collider),
+					&friendly_ships,
+					&faction_definition_list,
+				);
+				if !line_of_fire_clear {
+					continue;
+				}
+
 				// Set timer for RoF delay.
 				gun_cycle_timer.0 = Timer::from_seconds(gun_properties.rate_of_fire.recip(), false);
 
 				// Calculate random spread
-				let shot_deviation = (((rand::random::<f32>() + rand::random::<f32>()) / 2.0
-					- 0.5) * gun_properties.cartridge_data.bullet_spread_degrees)
+				let shot_deviation = (((rng.gen::<f32>() + rng.gen::<f32>()) / 2.0 - 0.5)
+					* gun_properties.cartridge_data.bullet_spread_degrees)
 					.to_radians();
 
 				// Add deviation to projectile velocity
@@ -101,11 +163,35 @@ pub fn gun_firing_system(
 					* gun_properties.cartridge_data.velocity_deviation_percent;
 				let turret_projectile_velocity =
 					gun_properties.cartridge_data.projectile_velocity_mps
-						+ (rand::random::<f32>() - 0.5) * velocity_deviation_mps;
+						+ (rng.gen::<f32>() - 0.5) * velocity_deviation_mps;
 
-				commands.spawn_bundle(projectile::ProjectileBundle {
-					damage: interaction::Damage(gun_properties.cartridge_data.projectile_damage),
-					iff: ship_iff.clone(),
+				let mut projectile = commands.spawn_bundle(projectile::ProjectileBundle {
+					source: projectile::ProjectileSource {
+						gun_name: gun_properties.gun_name,
+						projectile_name: gun_properties.cartridge_data.projectile_name,
+						ship: Some(turret_parent.0),
+					},
+					damage: interaction::Damage {
+						amount: gun_properties.cartridge_data.projectile_damage,
+						damage_type: gun_properties.cartridge_data.damage_type,
+						armor_penetration: gun_properties.cartridge_data.armor_penetration,
+						armor_piercing: gun_properties.cartridge_data.armor_piercing,
+					},
+					blast: interaction::Blast {
+						radius: gun_properties.cartridge_data.blast_radius,
+						falloff: gun_properties.cartridge_data.blast_falloff,
+					},
+					fuse: projectile::Fuse {
+						proximity_distance: gun_properties.cartridge_data.proximity_fuse_distance,
+						// Go off where the turret predicted the target would be
+						seconds_remaining: if gun_properties.cartridge_data.timed_fuse {
+							turret_properties.intercept_seconds
+						} else {
+							None
+						},
+						detonated: false,
+					},
+					faction: *ship_faction,
 					transform: Transform {
 						translation: gun_transform.translation + Vec3::new(0.0, 0.0, -10.0),
 						rotation: gun_transform.rotation,
@@ -119,20 +205,97 @@ pub fn gun_firing_system(
 						) * turret_projectile_velocity
 							+ ship_velocity.0,
 					),
-					sprite: Sprite {
-						custom_size: Some(gun_properties.cartridge_data.texture_render_size),
+					appearance: Appearance {
+						texture_path: Some(
+							BASE_TEXTURE_PATH_PROJECTILES.to_string()
+								+ &gun_properties.cartridge_data.texture_path,
+						),
+						size: gun_properties.cartridge_data.texture_render_size,
 						..default()
 					},
-					texture: asset_server.load(
-						&(BASE_TEXTURE_PATH_PROJECTILES.to_string()
-							+ &gun_properties.cartridge_data.texture_path),
-					),
 					..default()
 				});
+				// Projectiles with health can be shot down
+				if let Some(projectile_health) = gun_properties.cartridge_data.projectile_health {
+					projectile.insert_bundle((
+						ship::Health(projectile_health),
+						ship::MaxHealth(projectile_health),
+						physics::Collider(gun_properties.cartridge_data.texture_render_size),
+					));
+				}
+				if gun_properties.cartridge_data.targetable {
+					projectile.insert(projectile::Targetable);
+				}
 
-				// Play gunfire sound effect
-				audio.play(gun_properties.fire_sound.clone());
+				gun_fired_events.send(GunFiredEvent {
+					gun: gun_entity,
+					turret: parent_turret.0,
+					gun_name: gun_properties.gun_name,
+				});
 			}
 		}
 	}
 }
+
+/// True if a shot from ```muzzle``` along ```direction``` for ```distance``` meters stays clear of
+/// the firing ship's hull and every other ship its faction isn't hostile to.
+/// Turrets sit inside their own ship's collider, so only shots across the middle of the hull are blocked by it.
+/// Turrets themselves aren't checked, as they have no FactionName, but the ships they sit on are.
+fn line_of_fire_clear(
+	muzzle: Vec2,
+	direction: Vec2,
+	distance: f32,
+	(own_ship, own_faction, own_transform, own_collider): (
+		Entity,
+		&faction_list::FactionName,
+		&Transform,
+		&physics::Collider,
+	),
+	friendly_ships: &Query<
+		(
+			Entity,
+			&Transform,
+			&physics::Collider,
+			&faction_list::FactionName,
+		),
+		(With<ship::Health>, Without<projectile::IsProjectile>),
+	>,
+	faction_definition_list: &faction_list::FactionDefinitionList,
+) -> bool {
+	let shot_end = muzzle + direction * distance;
+
+	let hull_core_size = own_collider.0 * HULL_CORE_FRACTION;
+	let muzzle_in_core = physics::segment_intersects_box(
+		muzzle,
+		muzzle,
+		own_transform.translation,
+		own_transform.rotation,
+		hull_core_size,
+	);
+	if !muzzle_in_core
+		&& physics::segment_intersects_box(
+			muzzle,
+			shot_end,
+			own_transform.translation,
+			own_transform.rotation,
+			hull_core_size,
+		) {
+		return false;
+	}
+
+	// Colliders are axis aligned, like in the collision systems
+	return !friendly_ships
+		.iter()
+		.filter(|(entity, _, _, faction)| {
+			*entity != own_ship && !faction_definition_list.is_hostile(*own_faction, **faction)
+		})
+		.any(|(_, transform, collider, _)| {
+			physics::segment_intersects_box(
+				muzzle,
+				shot_end,
+				transform.translation,
+				Quat::IDENTITY,
+				collider.0,
+			)
+		});
+}
@@ -2,10 +2,17 @@ use super::*;
 
 const GUN_DATA_PATH: &str = "data/guns/";
 
-#[derive(PartialEq, Serialize, Deserialize, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, Debug)]
 pub enum GunName {
 	TemplateGun,
 	SmallMachinegun,
+	SmallRocketLauncher,
+}
+
+impl Default for GunName {
+	fn default() -> Self {
+		GunName::TemplateGun
+	}
 }
 
 /// Gun definition for storing gun parameters as YAML
@@ -39,7 +46,7 @@ pub fn write_gun_definition_template() {
 		rate_of_fire: 10.0,
 		texture_path: "template_texture.png".to_string(),
 		texture_render_size: Vec2::new(10.0, 10.0),
-    fire_sound_path: "template_audio.ogg".to_string(),
+		fire_sound_path: "template_audio.ogg".to_string(),
 	};
 	// Write out template
 	crate::game_io::write_definition_template(GUN_DATA_PATH, ship_definition_template);
@@ -75,9 +82,9 @@ pub fn turret_gun_spawn_system(
 							commands.entity(turret).with_children(|parent| {
 								parent.spawn_bundle(gun::GunBundle {
 									transform: gun_assignment.transform,
-									texture: gun_assignment.texture,
-									sprite: Sprite {
-										custom_size: Some(gun_assignment.texture_size),
+									appearance: Appearance {
+										texture_path: Some(gun_assignment.texture_path),
+										size: gun_assignment.texture_size,
 										..default()
 									},
 									..default()
@@ -0,0 +1,201 @@
+use super::*;
+
+/// Fraction of its MaxSpeed a ship keeps once its engines are destroyed
+const ENGINE_MIN_SPEED_FRACTION: f32 = 0.25;
+
+/// Hull section damage, and what it does to turrets and engines
+pub struct HullPlugin;
+
+impl Plugin for HullPlugin {
+	fn build(&self, app: &mut App) {
+		app.add_simulation_system_set(
+			SimulationStep::Update,
+			SystemSet::new()
+				.after(SimulationSystem::Damage)
+				.before(SimulationSystem::Cleanup)
+				.with_system(section_turret_disable_system.after(turret::turret_repair_system))
+				.with_system(engine_damage_system.after(section_turret_disable_system)),
+		);
+	}
+}
+
+/// Part of a hull that can be damaged on its own
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Debug)]
+pub enum HullSectionName {
+	Bow,
+	Midship,
+	Stern,
+	/// Slows the ship down as it is damaged
+	Engines,
+}
+
+/// Hull Section Definition for storing hull section data in Ship Definitions
+#[derive(Clone, Copy, Serialize, Deserialize, Debug)]
+pub struct HullSectionDefinition {
+	pub section_name: HullSectionName,
+	pub health: f32,
+	/// Center of the section, relative to the ship. Hits and turret mounts belong to the nearest section.
+	pub translation: Vec2,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct HullSection {
+	pub section_name: HullSectionName,
+	pub translation: Vec2,
+	pub health: f32,
+	pub max_health: f32,
+}
+
+impl HullSection {
+	pub fn is_destroyed(&self) -> bool {
+		self.health <= 0.0
+	}
+}
+
+// ==========
+// Components
+
+/// Damageable sections of a ship's hull. Hits still take Health, but also damage the section they land on.
+/// Ships without sections have an empty list.
+#[derive(Component, Clone, Debug)]
+pub struct HullSections {
+	pub sections: Vec<HullSection>,
+	/// Fraction of its MaxSpeed the ship's engines currently give it
+	pub engine_speed_fraction: f32,
+}
+
+impl Default for HullSections {
+	fn default() -> Self {
+		Self {
+			sections: Vec::new(),
+			engine_speed_fraction: 1.0,
+		}
+	}
+}
+
+impl HullSections {
+	pub fn new(section_definitions: &[HullSectionDefinition]) -> Self {
+		Self {
+			sections: section_definitions
+				.iter()
+				.map(|section_definition| HullSection {
+					section_name: section_definition.section_name,
+					translation: section_definition.translation,
+					health: section_definition.health,
+					max_health: section_definition.health,
+				})
+				.collect(),
+			engine_speed_fraction: 1.0,
+		}
+	}
+
+	/// Index of the section closest to ```local_position```, relative to the ship
+	pub fn nearest_section(&self, local_position: Vec2) -> Option<usize> {
+		return self
+			.sections
+			.iter()
+			.enumerate()
+			.min_by(|(_, a), (_, b)| {
+				a.translation
+					.distance_squared(local_position)
+					.total_cmp(&b.translation.distance_squared(local_position))
+			})
+			.map(|(index, _)| index);
+	}
+
+	/// Damages the section closest to ```local_position```.
+	/// Returns the section's name if the hit destroyed it.
+	pub fn damage(&mut self, local_position: Vec2, damage: f32) -> Option<HullSectionName> {
+		let index = self.nearest_section(local_position)?;
+		let section = &mut self.sections[index];
+		if section.is_destroyed() {
+			return None;
+		}
+		section.health -= damage;
+		if section.is_destroyed() {
+			return Some(section.section_name);
+		}
+		return None;
+	}
+
+	/// True if the section at ```index``` is destroyed
+	pub fn is_destroyed(&self, index: usize) -> bool {
+		self.sections
+			.get(index)
+			.is_some_and(|section| section.is_destroyed())
+	}
+
+	/// Fraction of MaxSpeed left with the Engines at their current health.
+	/// Ships without an Engines section always have full speed.
+	fn engine_speed_fraction_from_health(&self) -> f32 {
+		let mut health = 0.0;
+		let mut max_health = 0.0;
+		for section in self
+			.sections
+			.iter()
+			.filter(|section| section.section_name == HullSectionName::Engines)
+		{
+			health += section.health.max(0.0);
+			max_health += section.max_health;
+		}
+		if max_health <= 0.0 {
+			return 1.0;
+		}
+		return ENGINE_MIN_SPEED_FRACTION + (1.0 - ENGINE_MIN_SPEED_FRACTION) * health / max_health;
+	}
+}
+
+// ==========
+// Events
+
+/// Sent when a hit destroys one of a ship's Hull Sections
+#[derive(Debug)]
+pub struct HullSectionDestroyedEvent {
+	pub ship: Entity,
+	pub section_name: HullSectionName,
+}
+
+// ==========
+// Systems
+
+/// Disables turrets sitting on destroyed Hull Sections
+pub fn section_turret_disable_system(
+	mut turrets: Query<(
+		&mut turret::TurretProperties,
+		&turret::TurretMountNumber,
+		&Parent,
+	)>,
+	ships: Query<(&ship::ShipTurretMountList, &HullSections)>,
+) {
+	for (mut turret_properties, mount_number, parent) in turrets.iter_mut() {
+		if turret_properties.turret_state == turret::TurretState::Disabled {
+			continue;
+		}
+		let (turret_mount_list, hull_sections) = match ships.get(parent.0) {
+			Ok(val) => val,
+			Err(_) => continue,
+		};
+		let section_destroyed = turret_mount_list[mount_number.0]
+			.mount_section
+			.is_some_and(|section_index| hull_sections.is_destroyed(section_index));
+		if section_destroyed {
+			turret_properties.target_entity = None;
+			turret_properties.turret_state = turret::TurretState::Disabled;
+		}
+	}
+}
+
+/// Slows ships down as their Engines are damaged
+pub fn engine_damage_system(
+	mut ships: Query<(&mut HullSections, &mut physics::MaxSpeed), Changed<HullSections>>,
+) {
+	for (mut hull_sections, mut max_speed) in ships.iter_mut() {
+		let engine_speed_fraction = hull_sections.engine_speed_fraction_from_health();
+		if engine_speed_fraction == hull_sections.engine_speed_fraction {
+			continue;
+		}
+		// Scale by the change, so speed changes from elsewhere, like difficulty, are kept
+		max_speed.0 *= engine_speed_fraction / hull_sections.engine_speed_fraction;
+		hull_sections.engine_speed_fraction = engine_speed_fraction;
+	}
+}
@@ -1,28 +1,545 @@
 use super::*;
 
-#[derive(Component, Clone, Copy, PartialEq, Serialize, Deserialize, Debug)]
-pub enum IFF {
-	Neutral,
-	Friendly,
-	Enemy,
+/// Damage application and removal of destroyed entities
+pub struct InteractionPlugin;
+
+impl Plugin for InteractionPlugin {
+	fn build(&self, app: &mut App) {
+		app.init_resource::<FriendlyFire>()
+			.add_simulation_system_set(
+				SimulationStep::Update,
+				SystemSet::new()
+					.after(SimulationSystem::Collision)
+					.before(SimulationSystem::Damage)
+					.with_system(blast_system),
+			)
+			.add_simulation_system_set(
+				SimulationStep::Update,
+				SystemSet::new()
+					.label(SimulationSystem::Damage)
+					.after(SimulationSystem::Collision)
+					.with_system(damage_system),
+			)
+			.add_simulation_system_set(
+				SimulationStep::Update,
+				SystemSet::new()
+					.label(SimulationSystem::Cleanup)
+					.after(SimulationSystem::Damage)
+					.with_system(kill_system)
+					.with_system(projectile_shot_down_system)
+					.with_system(player_death_system.after(kill_system)),
+			);
+	}
+}
+
+/// Fraction of its damage a hit still does when armor stops it
+const MIN_DAMAGE_FRACTION: f32 = 0.1;
+
+/// Kind of damage, which armor and shields resist differently
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, Debug)]
+pub enum DamageType {
+	Kinetic,
+	Explosive,
+	Energy,
+}
+
+impl Default for DamageType {
+	fn default() -> Self {
+		DamageType::Kinetic
+	}
 }
 
-impl Default for IFF{
-    fn default() -> Self {
-        IFF::Neutral
-    }
+/// Damage done on hitting a target, before shields and armor
+#[derive(Component, Clone, Copy, Default, Debug)]
+pub struct Damage {
+	pub amount: f32,
+	pub damage_type: DamageType,
+	/// Armor ignored by the hit
+	pub armor_penetration: f32,
+	/// Armor piercing hits do full damage when they penetrate, but are stopped by armor they can't
+	pub armor_piercing: bool,
 }
 
-#[derive(Component, Deref, DerefMut, Default, Debug)]
-pub struct Damage(pub f32);
+/// Splash damage done around where a projectile detonates
+#[derive(Component, Clone, Copy, Default, Debug)]
+pub struct Blast {
+	/// Furthest splash damage reaches, in meters. 0 for direct hits only.
+	pub radius: f32,
+	/// Fraction of the damage lost by the edge of the blast
+	pub falloff: f32,
+}
 
+impl Blast {
+	/// Fraction of the damage done ```distance``` meters from the blast, 0 outside it.
+	/// Kept between 0 and 1, whatever the falloff.
+	pub fn damage_fraction(&self, distance: f32) -> f32 {
+		if self.radius <= 0.0 || distance > self.radius {
+			return 0.0;
+		}
+		return (1.0 - self.falloff * distance / self.radius).clamp(0.0, 1.0);
+	}
+}
+
+/// Armor against each damage type, taken off the damage of every hit
+#[derive(Component, Clone, Copy, Default, Serialize, Deserialize, Debug)]
+pub struct Armor {
+	pub kinetic: f32,
+	pub explosive: f32,
+	pub energy: f32,
+}
+
+impl Armor {
+	/// Armor value against ```damage_type```
+	pub fn against(&self, damage_type: DamageType) -> f32 {
+		match damage_type {
+			DamageType::Kinetic => self.kinetic,
+			DamageType::Explosive => self.explosive,
+			DamageType::Energy => self.energy,
+		}
+	}
+}
+
+/// Whether projectiles and blasts damage ships of allied factions
+#[derive(Clone, Copy, Deref, DerefMut, Default, Debug)]
+pub struct FriendlyFire(pub bool);
+
+/// Marks an entity that currently ignores all damage, like a shielded stronghold section
+#[derive(Component, Debug)]
+pub struct Invulnerable;
+
+// ==========
+// Events
+
+/// Sent when an entity with ```ship::Health``` should take damage
+#[derive(Debug)]
+pub struct DamageEvent {
+	pub target: Entity,
+	/// Damage as it hits, before the target's Shield and armor
+	pub damage: Damage,
+	/// Where the damage came from, if it was a projectile
+	pub source: Option<projectile::ProjectileSource>,
+	/// Where the hit landed, for damaging the target's Hull Sections
+	pub impact: Option<Vec2>,
+	/// Whether the target was struck directly, rather than splashed by a blast
+	pub direct_hit: bool,
+}
+
+/// Sent when an entity is destroyed, with what's needed to show its destruction after it is gone
+#[derive(Debug)]
+pub struct DestroyedEvent {
+	pub entity: Entity,
+	pub faction: faction_list::FactionName,
+	pub translation: Vec3,
+	pub velocity: Vec2,
+	pub destruction_effect: Option<destruction::DestructionEffect>,
+}
+
+/// Sent when a projectile with a Blast detonates, on a target or by its Fuse
+#[derive(Debug)]
+pub struct DetonationEvent {
+	pub translation: Vec2,
+	pub damage: Damage,
+	pub blast: Blast,
+	/// Targets allied with this faction are spared
+	pub faction: faction_list::FactionName,
+	pub source: Option<projectile::ProjectileSource>,
+	/// Target the projectile hit, which already took the direct hit damage
+	pub direct_hit: Option<Entity>,
+}
+
+/// Sent once when the player ship is destroyed
+#[derive(Debug)]
+pub struct GameOverEvent {
+	pub player_ship: Entity,
+}
+
+/// Returns how much of ```damage``` gets through ```armor```.
+/// Armor, less the hit's penetration, is taken off the damage.
+/// Armor piercing hits do full damage if they penetrate the armor, and are stopped if they don't.
+/// Stopped hits still do ```MIN_DAMAGE_FRACTION``` of their damage.
+pub fn resolve_damage(damage: &Damage, armor: Option<&Armor>) -> f32 {
+	let armor_value = match armor {
+		Some(armor) => armor.against(damage.damage_type),
+		None => return damage.amount,
+	};
+	let effective_armor = (armor_value - damage.armor_penetration).max(0.0);
+	let minimum_damage = damage.amount * MIN_DAMAGE_FRACTION;
+
+	if damage.armor_piercing {
+		if effective_armor <= 0.0 {
+			return damage.amount;
+		}
+		return minimum_damage;
+	}
+	return (damage.amount - effective_armor).max(minimum_damage);
+}
+
+// ==========
+// Systems
+
+/// Applies all damage sent this tick to the targets' Shield, then what gets through to their armor
+/// and Health, unless they are Invulnerable.
+/// Damage that gets through also hits the Hull Section nearest the impact.
+/// Turrets take their ship's armor.
+pub fn damage_system(
+	mut damage_events: EventReader<DamageEvent>,
+	mut shield_down_events: EventWriter<shield::ShieldDownEvent>,
+	mut section_destroyed_events: EventWriter<hull::HullSectionDestroyedEvent>,
+	mut targets: Query<
+		(
+			&mut ship::Health,
+			Option<&mut shield::Shield>,
+			Option<&Armor>,
+			Option<&mut hull::HullSections>,
+			&Transform,
+			Option<&Parent>,
+		),
+		Without<Invulnerable>,
+	>,
+	ship_armor: Query<&Armor>,
+) {
+	for damage_event in damage_events.iter() {
+		// Target may have been removed since the event was sent
+		if let Ok((
+			mut target_health,
+			target_shield,
+			target_armor,
+			target_hull_sections,
+			target_transform,
+			target_parent,
+		)) = targets.get_mut(damage_event.target)
+		{
+			let mut damage = damage_event.damage;
+			if let Some(mut target_shield) = target_shield {
+				let shield_was_up = target_shield.is_up();
+				damage.amount = target_shield.absorb(damage.amount, damage.damage_type);
+				if shield_was_up && !target_shield.is_up() {
+					shield_down_events.send(shield::ShieldDownEvent {
+						entity: damage_event.target,
+					});
+				}
+			}
+			let target_armor = target_armor.or_else(|| {
+				target_parent.and_then(|target_parent| ship_armor.get(target_parent.0).ok())
+			});
+			let damage = resolve_damage(&damage, target_armor);
+			target_health.0 -= damage;
+
+			if let (Some(mut target_hull_sections), Some(impact)) =
+				(target_hull_sections, damage_event.impact)
+			{
+				if target_hull_sections.sections.is_empty() || damage <= 0.0 {
+					continue;
+				}
+				let local_impact = (target_transform.rotation.inverse()
+					* (impact.extend(0.0) - target_transform.translation))
+					.truncate();
+				if let Some(section_name) = target_hull_sections.damage(local_impact, damage) {
+					section_destroyed_events.send(hull::HullSectionDestroyedEvent {
+						ship: damage_event.target,
+						section_name,
+					});
+				}
+			}
+		}
+	}
+}
+
+/// Splashes damage from every detonation onto the non-allied ships in its blast.
+/// With Friendly Fire on, allied ships are caught in it too, except the ship that fired.
+/// Turrets are splashed as their ship's faction, unless its raised Shield covers them or it is Invulnerable.
+/// Damage falls off with distance from the blast to the edge of the target's Collider.
+pub fn blast_system(
+	friendly_fire: Res<FriendlyFire>,
+	faction_definition_list: Res<faction_list::FactionDefinitionList>,
+	mut detonation_events: EventReader<DetonationEvent>,
+	mut damage_events: EventWriter<DamageEvent>,
+	targets: Query<
+		(
+			Entity,
+			&Transform,
+			&physics::Collider,
+			&faction_list::FactionName,
+		),
+		With<ship::Health>,
+	>,
+	turrets: Query<
+		(
+			Entity,
+			&Transform,
+			&physics::Collider,
+			&ship::Health,
+			&Parent,
+		),
+		With<turret::IsTurret>,
+	>,
+	invulnerable: Query<(), With<Invulnerable>>,
+	shields: Query<&shield::Shield>,
+) {
+	for detonation_event in detonation_events.iter() {
+		let firing_ship = detonation_event.source.and_then(|source| source.ship);
+		let spared = |target_entity: Entity, ship_entity: Entity, target_faction| {
+			return (faction_definition_list.is_allied(detonation_event.faction, target_faction)
+				&& !friendly_fire.0)
+				|| firing_ship == Some(ship_entity)
+				|| detonation_event.direct_hit == Some(target_entity);
+		};
+
+		let mut splashed = Vec::new();
+		for (target_entity, target_transform, target_collider, target_faction) in targets.iter() {
+			if !spared(target_entity, target_entity, *target_faction) {
+				splashed.push((target_entity, target_transform.translation, target_collider));
+			}
+		}
+		for (turret_entity, turret_transform, turret_collider, turret_health, turret_parent) in
+			turrets.iter()
+		{
+			if turret_health.0 <= 0.0
+				|| invulnerable.get(turret_parent.0).is_ok()
+				|| shields
+					.get(turret_parent.0)
+					.is_ok_and(|ship_shield| ship_shield.is_up())
+			{
+				continue;
+			}
+			let (ship_transform, ship_faction) = match targets.get(turret_parent.0) {
+				Ok((_, ship_transform, _, ship_faction)) => (ship_transform, ship_faction),
+				Err(_) => continue,
+			};
+			if !spared(turret_entity, turret_parent.0, *ship_faction) {
+				splashed.push((
+					turret_entity,
+					ship_transform.mul_transform(*turret_transform).translation,
+					turret_collider,
+				));
+			}
+		}
+
+		for (target_entity, target_translation, target_collider) in splashed {
+			let distance = physics::distance_to_collider(
+				detonation_event.translation,
+				target_translation,
+				target_collider,
+			);
+			let damage_fraction = detonation_event.blast.damage_fraction(distance);
+			if damage_fraction <= 0.0 {
+				continue;
+			}
+			let splash_damage = Damage {
+				amount: detonation_event.damage.amount * damage_fraction,
+				..detonation_event.damage
+			};
+			damage_events.send(DamageEvent {
+				target: target_entity,
+				damage: splash_damage,
+				source: detonation_event.source,
+				impact: Some(detonation_event.translation),
+				direct_hit: false,
+			});
+		}
+	}
+}
+
+/// Despawns everything but the player ship once its Health runs out, along with its turrets and guns.
+/// Turrets are taken offline instead, see ```turret::turret_destroyed_system```, and projectiles are shot down.
 pub fn kill_system(
 	mut commands: Commands,
-	enemy: Query<(Entity, &ship::Health), Changed<ship::Health>>,
+	mut destroyed_events: EventWriter<DestroyedEvent>,
+	enemy: Query<
+		(
+			Entity,
+			&ship::Health,
+			&faction_list::FactionName,
+			&Transform,
+			Option<&physics::Velocity>,
+			Option<&destruction::DestructionEffect>,
+		),
+		(
+			Changed<ship::Health>,
+			Without<ship::IsPlayerShip>,
+			Without<turret::IsTurret>,
+			Without<projectile::IsProjectile>,
+		),
+	>,
+) {
+	for (entity, health, faction, transform, velocity, destruction_effect) in enemy.iter() {
+		if health.0 <= 0.0 {
+			commands.entity(entity).despawn_recursive();
+			destroyed_events.send(DestroyedEvent {
+				entity,
+				faction: *faction,
+				translation: transform.translation,
+				velocity: velocity.map_or(Vec2::ZERO, |velocity| velocity.0),
+				destruction_effect: destruction_effect.cloned(),
+			});
+		}
+	}
+}
+
+/// Despawns projectiles, like missiles, once their Health runs out. They don't detonate.
+pub fn projectile_shot_down_system(
+	mut commands: Commands,
+	projectiles: Query<
+		(Entity, &ship::Health),
+		(Changed<ship::Health>, With<projectile::IsProjectile>),
+	>,
 ) {
-	for (entity, health) in enemy.iter() {
+	for (entity, health) in projectiles.iter() {
 		if health.0 <= 0.0 {
 			commands.entity(entity).despawn();
 		}
 	}
 }
+
+/// Ends the game when the player ship's Health runs out. The ship is kept, but marked as destroyed.
+pub fn player_death_system(
+	mut commands: Commands,
+	mut game_over_events: EventWriter<GameOverEvent>,
+	mut destroyed_events: EventWriter<DestroyedEvent>,
+	player_ship: Query<
+		(
+			Entity,
+			&ship::Health,
+			&faction_list::FactionName,
+			&Transform,
+			&physics::Velocity,
+			Option<&destruction::DestructionEffect>,
+		),
+		(
+			With<ship::IsPlayerShip>,
+			Without<ship::IsDestroyed>,
+			Changed<ship::Health>,
+		),
+	>,
+) {
+	for (player_ship_entity, health, faction, transform, velocity, destruction_effect) in
+		player_ship.iter()
+	{
+		if health.0 <= 0.0 {
+			commands
+				.entity(player_ship_entity)
+				.insert(ship::IsDestroyed);
+			destroyed_events.send(DestroyedEvent {
+				entity: player_ship_entity,
+				faction: *faction,
+				translation: transform.translation,
+				velocity: velocity.0,
+				destruction_effect: destruction_effect.cloned(),
+			});
+			game_over_events.send(GameOverEvent {
+				player_ship: player_ship_entity,
+			});
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const TOLERANCE: f32 = 1e-3;
+
+	fn assert_close(actual: f32, expected: f32) {
+		assert!(
+			(actual - expected).abs() < TOLERANCE,
+			"expected {}, got {}",
+			expected,
+			actual
+		);
+	}
+
+	const ARMOR: Armor = Armor {
+		kinetic: 5.0,
+		explosive: 2.0,
+		energy: 0.0,
+	};
+
+	fn kinetic_hit(amount: f32, armor_penetration: f32, armor_piercing: bool) -> Damage {
+		return Damage {
+			amount,
+			damage_type: DamageType::Kinetic,
+			armor_penetration,
+			armor_piercing,
+		};
+	}
+
+	#[test]
+	fn blast_damage_falls_off_to_the_edge() {
+		let blast = Blast {
+			radius: 10.0,
+			falloff: 0.5,
+		};
+		assert_close(blast.damage_fraction(0.0), 1.0);
+		assert_close(blast.damage_fraction(5.0), 0.75);
+		assert_close(blast.damage_fraction(10.0), 0.5);
+		assert_close(blast.damage_fraction(10.5), 0.0);
+	}
+
+	#[test]
+	fn blast_damage_fraction_stays_between_zero_and_one() {
+		let steep_blast = Blast {
+			radius: 10.0,
+			falloff: 2.0,
+		};
+		assert_close(steep_blast.damage_fraction(10.0), 0.0);
+		let negative_blast = Blast {
+			radius: 10.0,
+			falloff: -1.0,
+		};
+		assert_close(negative_blast.damage_fraction(10.0), 1.0);
+	}
+
+	#[test]
+	fn unarmored_targets_take_full_damage() {
+		assert_close(resolve_damage(&kinetic_hit(10.0, 0.0, false), None), 10.0);
+	}
+
+	#[test]
+	fn armor_is_taken_off_the_damage() {
+		assert_close(
+			resolve_damage(&kinetic_hit(10.0, 0.0, false), Some(&ARMOR)),
+			5.0,
+		);
+		assert_close(
+			resolve_damage(&kinetic_hit(10.0, 3.0, false), Some(&ARMOR)),
+			8.0,
+		);
+	}
+
+	#[test]
+	fn armor_is_resisted_by_damage_type() {
+		let hit = Damage {
+			damage_type: DamageType::Explosive,
+			..kinetic_hit(10.0, 0.0, false)
+		};
+		assert_close(resolve_damage(&hit, Some(&ARMOR)), 8.0);
+	}
+
+	#[test]
+	fn piercing_hits_that_penetrate_do_full_damage() {
+		assert_close(
+			resolve_damage(&kinetic_hit(10.0, 5.0, true), Some(&ARMOR)),
+			10.0,
+		);
+		assert_close(
+			resolve_damage(&kinetic_hit(10.0, 8.0, true), Some(&ARMOR)),
+			10.0,
+		);
+	}
+
+	#[test]
+	fn piercing_hits_that_are_stopped_do_minimum_damage() {
+		assert_close(
+			resolve_damage(&kinetic_hit(10.0, 4.0, true), Some(&ARMOR)),
+			10.0 * MIN_DAMAGE_FRACTION,
+		);
+	}
+
+	#[test]
+	fn heavy_armor_still_lets_minimum_damage_through() {
+		assert_close(
+			resolve_damage(&kinetic_hit(3.0, 0.0, false), Some(&ARMOR)),
+			3.0 * MIN_DAMAGE_FRACTION,
+		);
+	}
+}
@@ -0,0 +1,146 @@
+use super::*;
+
+/// Direction the level's track runs in, towards the stronghold
+const TRACK_DIRECTION: Vec2 = Vec2::Y;
+
+/// Player throttle orders and progress along the level towards the stronghold
+pub struct LevelPlugin;
+
+impl Plugin for LevelPlugin {
+	fn build(&self, app: &mut App) {
+		app.init_resource::<CurrentLevel>()
+			.init_resource::<LevelProgress>()
+			.add_simulation_system_set(
+				SimulationStep::Update,
+				SystemSet::new()
+					.label(SimulationSystem::Input)
+					.before(SimulationSystem::Targeting)
+					.with_system(throttle_system),
+			)
+			.add_simulation_system_set(
+				SimulationStep::Update,
+				SystemSet::new()
+					.after(SimulationSystem::Physics)
+					.before(SimulationSystem::Cleanup)
+					.with_system(level_progress_system),
+			);
+	}
+}
+
+/// Fraction (0 to 1) of its MaxSpeed the ship has been ordered to fly forwards at
+#[derive(Component, Clone, Copy, Deref, DerefMut, Default, Debug)]
+pub struct Throttle(pub f32);
+
+impl Throttle {
+	/// Changes the throttle by ```change```, keeping it between 0 and 1
+	pub fn adjust(&mut self, change: f32) {
+		self.0 = (self.0 + change).clamp(0.0, 1.0);
+	}
+}
+
+/// Level being played
+#[derive(Deref, DerefMut, Default, Debug)]
+pub struct CurrentLevel(pub level_list::LevelName);
+
+/// How far along the level the player ship is
+#[derive(Default, Debug)]
+pub struct LevelProgress {
+	/// Meters travelled forwards along the track
+	pub distance_travelled: f32,
+	pub track_length: f32,
+	/// Whether the stronghold has been reached
+	pub arrived: bool,
+	/// Whether the stronghold has been spawned since arriving
+	pub stronghold_spawned: bool,
+	/// Whether the stronghold has been defeated
+	pub stage_complete: bool,
+}
+
+impl LevelProgress {
+	/// Fraction (0 to 1) of the track travelled
+	pub fn fraction(&self) -> f32 {
+		if self.track_length <= 0.0 {
+			return 1.0;
+		}
+		return (self.distance_travelled / self.track_length).clamp(0.0, 1.0);
+	}
+}
+
+// ==========
+// Events
+
+/// Sent once when the player ship reaches the end of the track
+#[derive(Debug)]
+pub struct StrongholdReachedEvent {
+	pub level_name: level_list::LevelName,
+}
+
+/// Returns the throttle the player ship starts ```level_name``` at
+pub fn starting_throttle(
+	level_name: level_list::LevelName,
+	level_definition_list: &Res<level_list::LevelDefinitionList>,
+) -> Throttle {
+	match level_definition_list
+		.iter()
+		.find(|&level_definition| level_definition.level_name == level_name)
+	{
+		Some(val) => Throttle(val.starting_throttle),
+		None => panic!("Failed to find {:?} in Level definitions list!", level_name),
+	}
+}
+
+// ==========
+// Systems
+
+/// Sets the Velocity of ships with a Throttle to fly forwards at their ordered speed.
+/// Destroyed ships come to a stop.
+pub fn throttle_system(
+	mut ships: Query<(
+		&mut physics::Velocity,
+		&Throttle,
+		&physics::MaxSpeed,
+		&Transform,
+		Option<&ship::IsDestroyed>,
+	)>,
+) {
+	for (mut velocity, throttle, max_speed, transform, destroyed) in ships.iter_mut() {
+		if destroyed.is_some() {
+			velocity.0 = Vec2::ZERO;
+			continue;
+		}
+		let forward = (transform.rotation * Vec3::Y).truncate();
+		velocity.0 = forward * throttle.0 * max_speed.0;
+	}
+}
+
+/// Tracks the player ship's progress along the track, and reports arriving at the stronghold
+pub fn level_progress_system(
+	mut level_progress: ResMut<LevelProgress>,
+	mut stronghold_reached_events: EventWriter<StrongholdReachedEvent>,
+	current_level: Res<CurrentLevel>,
+	level_definition_list: Res<level_list::LevelDefinitionList>,
+	player_ship: Query<&physics::Velocity, With<ship::IsPlayerShip>>,
+) {
+	level_progress.track_length = match level_definition_list
+		.iter()
+		.find(|&level_definition| level_definition.level_name == current_level.0)
+	{
+		Some(val) => val.track_length,
+		None => panic!(
+			"Failed to find {:?} in Level definitions list!",
+			current_level.0
+		),
+	};
+
+	if let Ok(player_velocity) = player_ship.get_single() {
+		level_progress.distance_travelled +=
+			player_velocity.dot(TRACK_DIRECTION) * SIMULATION_TIMESTEP as f32;
+	}
+
+	if !level_progress.arrived && level_progress.distance_travelled >= level_progress.track_length {
+		level_progress.arrived = true;
+		stronghold_reached_events.send(StrongholdReachedEvent {
+			level_name: current_level.0,
+		});
+	}
+}
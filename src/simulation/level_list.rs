@@ -0,0 +1,55 @@
+use super::*;
+
+const LEVEL_DATA_PATH: &str = "data/levels/";
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Debug)]
+pub enum LevelName {
+	TemplateLevel,
+	FirstStronghold,
+}
+
+impl Default for LevelName {
+	fn default() -> Self {
+		LevelName::FirstStronghold
+	}
+}
+
+/// Level definition for storing the run up to a stronghold as YAML
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LevelDefinition {
+	pub level_name: LevelName,
+	/// Meters the player ship has to travel forwards to reach the stronghold
+	pub track_length: f32,
+	/// Throttle (0 to 1) the player ship starts the level at
+	pub starting_throttle: f32,
+	/// Stronghold waiting at the end of the track
+	pub stronghold_name: stronghold_list::StrongholdName,
+	/// How far ahead of the player ship the stronghold appears on arrival
+	pub stronghold_distance: f32,
+}
+
+/// List of Level Definitions
+#[derive(Deref, DerefMut, Debug)]
+pub struct LevelDefinitionList(Vec<LevelDefinition>);
+
+/// Generates an explanatory template for how a Level Definition should be formatted in YAML,
+/// then writes it out to a template.yaml file.
+pub fn write_level_definition_template() {
+	// Define template
+	let level_definition_template = LevelDefinition {
+		level_name: LevelName::TemplateLevel,
+		track_length: 5000.0,
+		starting_throttle: 0.5,
+		stronghold_name: stronghold_list::StrongholdName::TemplateStronghold,
+		stronghold_distance: 900.0,
+	};
+	// Write out template
+	crate::game_io::write_definition_template(LEVEL_DATA_PATH, level_definition_template);
+}
+
+/// Reads all *.yaml Level definition files in data/levels/ and returns them as a ```LevelDefinitionList```
+pub fn read_level_definitions() -> LevelDefinitionList {
+	let level_definition_list =
+		LevelDefinitionList(crate::game_io::read_definitions(LEVEL_DATA_PATH));
+	return level_definition_list;
+}
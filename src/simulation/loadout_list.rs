@@ -0,0 +1,78 @@
+use super::*;
+
+const LOADOUT_DATA_PATH: &str = "data/loadouts/";
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Debug)]
+pub enum LoadoutName {
+	TemplateLoadout,
+	PlayerDefault,
+	EscortCorvette,
+}
+
+impl Default for LoadoutName {
+	fn default() -> Self {
+		LoadoutName::PlayerDefault
+	}
+}
+
+/// Turret to fit to one of a ship's turret mounts
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TurretLoadoutDefinition {
+	pub mount_number: usize,
+	pub gun_name: gun_list::GunName,
+	pub num_barrels: turret::TurretNumBarrels,
+	/// Cartridge to load instead of the gun's standard one, like armor piercing or high explosive rounds
+	pub ammunition: Option<cartridge_list::ProjectileName>,
+	/// What the turret engages first
+	pub role: turret::TurretRole,
+}
+
+/// Loadout definition for storing a ship hull and the turrets fitted to it as YAML
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LoadoutDefinition {
+	pub loadout_name: LoadoutName,
+	pub ship_name: ship_list::ShipName,
+	pub turrets: Vec<TurretLoadoutDefinition>,
+	/// Upgrades fitted to the ship, at most one of each category
+	pub upgrades: Vec<upgrade_list::UpgradeName>,
+}
+
+/// List of Loadout Definitions
+#[derive(Deref, DerefMut, Debug)]
+pub struct LoadoutDefinitionList(Vec<LoadoutDefinition>);
+
+/// Generates an explanatory template for how a Loadout Definition should be formatted in YAML,
+/// then writes it out to a template.yaml file.
+pub fn write_loadout_definition_template() {
+	// Define template
+	let loadout_definition_template = LoadoutDefinition {
+		loadout_name: LoadoutName::TemplateLoadout,
+		ship_name: ship_list::ShipName::TemplateShip,
+		turrets: vec![
+			TurretLoadoutDefinition {
+				mount_number: 0,
+				gun_name: gun_list::GunName::TemplateGun,
+				num_barrels: turret::TurretNumBarrels::Single,
+				ammunition: None,
+				role: turret::TurretRole::General,
+			},
+			TurretLoadoutDefinition {
+				mount_number: 2,
+				gun_name: gun_list::GunName::TemplateGun,
+				num_barrels: turret::TurretNumBarrels::Triple,
+				ammunition: Some(cartridge_list::ProjectileName::TemplateProjectile),
+				role: turret::TurretRole::PointDefence,
+			},
+		],
+		upgrades: vec![upgrade_list::UpgradeName::TemplateUpgrade],
+	};
+	// Write out template
+	crate::game_io::write_definition_template(LOADOUT_DATA_PATH, loadout_definition_template);
+}
+
+/// Reads all *.yaml Loadout definition files in data/loadouts/ and returns them as a ```LoadoutDefinitionList```
+pub fn read_loadout_definitions() -> LoadoutDefinitionList {
+	let loadout_definition_list =
+		LoadoutDefinitionList(crate::game_io::read_definitions(LOADOUT_DATA_PATH));
+	return loadout_definition_list;
+}
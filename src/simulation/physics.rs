@@ -1,8 +1,31 @@
 use std::ops::Sub;
 
-use super::{interaction::IFF, *};
+use super::{faction_list::FactionName, *};
 use bevy::sprite::collide_aabb::collide;
 
+/// Movement and collision detection
+pub struct PhysicsPlugin;
+
+impl Plugin for PhysicsPlugin {
+	fn build(&self, app: &mut App) {
+		app.add_simulation_system_set(
+			SimulationStep::Update,
+			SystemSet::new()
+				.label(SimulationSystem::Physics)
+				.after(SimulationSystem::Weapons)
+				.with_system(object_movement_system),
+		)
+		.add_simulation_system_set(
+			SimulationStep::Update,
+			SystemSet::new()
+				.label(SimulationSystem::Collision)
+				.after(SimulationSystem::Physics)
+				.with_system(projectile_fuse_system.before(projectile_collision_system))
+				.with_system(projectile_collision_system),
+		);
+	}
+}
+
 #[derive(Component, Deref, DerefMut, Default, Debug)]
 pub struct Velocity(pub Vec2);
 
@@ -10,7 +33,7 @@ impl Sub for Velocity {
 	type Output = Self;
 
 	fn sub(self, rhs: Self) -> Self::Output {
-		Self { 0: self.0 - rhs.0 }
+		Self(self.0 - rhs.0)
 	}
 }
 
@@ -21,50 +44,403 @@ impl Sub for Acceleration {
 	type Output = Self;
 
 	fn sub(self, rhs: Self) -> Self::Output {
-		Self { 0: self.0 - rhs.0 }
+		Self(self.0 - rhs.0)
 	}
 }
 
+/// Rotation applied to the entity every simulation tick
 #[derive(Component, Deref, DerefMut)]
 pub struct VelocityRotational(pub Quat);
 
+/// Size of the entity's axis aligned bounding box for collisions, in meters
+#[derive(Component, Clone, Copy, Deref, DerefMut, Serialize, Deserialize, Debug)]
+pub struct Collider(pub Vec2);
+
+impl Default for Collider {
+	fn default() -> Self {
+		Collider(Vec2::new(1.0, 1.0))
+	}
+}
+
+/// Top speed the entity is allowed to move at
+#[derive(Component, Clone, Copy, Deref, DerefMut, Default, Serialize, Deserialize, Debug)]
+pub struct MaxSpeed(pub f32);
+
+/// Largest Acceleration the entity can steer with
+#[derive(Component, Clone, Copy, Deref, DerefMut, Default, Serialize, Deserialize, Debug)]
+pub struct MaxAcceleration(pub f32);
+
 // ==========
 // SYSTEMS
 
-/// Updates the position of entities with a Velocity and a Transform
+/// Applies Acceleration to Velocity, keeping under MaxSpeed if there is one,
+/// then updates the position of entities with a Velocity and a Transform,
+/// and spins those with a VelocityRotational
 pub fn object_movement_system(
-	mut movement_query: Query<(&Velocity, &mut Transform)>,
-	time: Res<Time>,
+	mut movement_query: Query<(
+		&mut Velocity,
+		Option<&Acceleration>,
+		Option<&MaxSpeed>,
+		Option<&VelocityRotational>,
+		&mut Transform,
+	)>,
 ) {
-	for (velocity, mut transform) in movement_query.iter_mut() {
-		transform.translation += velocity.extend(0.0) * time.delta_seconds();
+	let timestep = SIMULATION_TIMESTEP as f32;
+	for (mut velocity, acceleration, max_speed, velocity_rotational, mut transform) in
+		movement_query.iter_mut()
+	{
+		if let Some(acceleration) = acceleration {
+			velocity.0 += acceleration.0 * timestep;
+			if let Some(max_speed) = max_speed {
+				velocity.0 = velocity.clamp_length_max(max_speed.0);
+			}
+		}
+		transform.translation += velocity.extend(0.0) * timestep;
+		if let Some(velocity_rotational) = velocity_rotational {
+			transform.rotation = velocity_rotational.0 * transform.rotation;
+		}
 	}
 }
 
+/// Despawns projectiles that hit a target not allied with their faction,
+/// and sends a DamageEvent for the target.
+/// Turrets sit on top of their ship's hull, so they are hit first, but only once their ship's Shield is down.
+/// Destroyed turrets, and turrets on Invulnerable ships, are passed over.
+/// Projectiles with a Blast also detonate on the target, splashing everything around it.
+/// With Friendly Fire on, allied ships and turrets are hit too, except the ship that fired.
 pub fn projectile_collision_system(
 	mut commands: Commands,
-	projectile_query: Query<(Entity, &interaction::Damage, &Transform, &IFF)>,
-	mut target_query: Query<(&mut ship::Health, &Transform, &IFF)>,
+	friendly_fire: Res<interaction::FriendlyFire>,
+	faction_definition_list: Res<faction_list::FactionDefinitionList>,
+	mut damage_events: EventWriter<interaction::DamageEvent>,
+	mut detonation_events: EventWriter<interaction::DetonationEvent>,
+	projectile_query: Query<(
+		Entity,
+		&interaction::Damage,
+		&interaction::Blast,
+		&projectile::ProjectileSource,
+		&Transform,
+		&Collider,
+		&FactionName,
+		&projectile::Fuse,
+	)>,
+	target_query: Query<
+		(
+			Entity,
+			&Transform,
+			&Collider,
+			&FactionName,
+			Option<&projectile::IsProjectile>,
+		),
+		(With<ship::Health>, Without<turret::IsTurret>),
+	>,
+	turret_query: Query<
+		(Entity, &Transform, &Collider, &ship::Health, &Parent),
+		With<turret::IsTurret>,
+	>,
+	invulnerable_query: Query<(), With<interaction::Invulnerable>>,
+	shield_query: Query<&shield::Shield>,
 ) {
-	for (projectile_entity, damage, projectile_transform, projectile_iff) in projectile_query.iter()
+	for (
+		projectile_entity,
+		damage,
+		blast,
+		projectile_source,
+		projectile_transform,
+		projectile_collider,
+		projectile_faction,
+		fuse,
+	) in projectile_query.iter()
 	{
-		for (mut target_health, target_transform, target_iff) in target_query.iter_mut() {
-			// Skip collision checks for friendly targets
-			if projectile_iff == target_iff {
+		if fuse.detonated {
+			continue; // Went off before reaching anything
+		}
+		let mut hit = None;
+		for (turret_entity, turret_transform, turret_collider, turret_health, turret_parent) in
+			turret_query.iter()
+		{
+			if turret_health.0 <= 0.0 || invulnerable_query.get(turret_parent.0).is_ok() {
+				continue;
+			}
+			// A raised Shield covers the turrets too, so the hit lands on the ship and its Shield
+			if shield_query
+				.get(turret_parent.0)
+				.is_ok_and(|ship_shield| ship_shield.is_up())
+			{
+				continue;
+			}
+			let (ship_transform, ship_faction) = match target_query.get(turret_parent.0) {
+				Ok((_, ship_transform, _, ship_faction, _)) => (ship_transform, ship_faction),
+				Err(_) => continue,
+			};
+			if projectile_source.ship == Some(turret_parent.0)
+				|| (faction_definition_list.is_allied(*projectile_faction, *ship_faction)
+					&& !friendly_fire.0)
+			{
 				continue;
 			}
 
+			// Composed from the ship's pose, so turrets move with their ship on every tick
+			let turret_translation = ship_transform.mul_transform(*turret_transform).translation;
 			let collision = collide(
-				target_transform.translation,
-				Vec2::new(target_transform.scale.x, target_transform.scale.y),
+				turret_translation,
+				turret_collider.0,
 				projectile_transform.translation,
-				Vec2::new(projectile_transform.scale.x, projectile_transform.scale.y),
+				projectile_collider.0,
 			);
-
 			if collision.is_some() {
-				target_health.0 -= damage.0;
-				commands.entity(projectile_entity).despawn();
+				hit = Some(turret_entity);
+				break;
+			}
+		}
+
+		if hit.is_none() {
+			for (
+				target_entity,
+				target_transform,
+				target_collider,
+				target_faction,
+				target_projectile,
+			) in target_query.iter()
+			{
+				// Skip collision checks for the firing ship, and allied targets unless Friendly Fire is on.
+				// Allied projectiles are never hit.
+				if target_entity == projectile_entity
+					|| projectile_source.ship == Some(target_entity)
+					|| (faction_definition_list.is_allied(*projectile_faction, *target_faction)
+						&& (!friendly_fire.0 || target_projectile.is_some()))
+				{
+					continue;
+				}
+
+				let collision = collide(
+					target_transform.translation,
+					target_collider.0,
+					projectile_transform.translation,
+					projectile_collider.0,
+				);
+				if collision.is_some() {
+					hit = Some(target_entity);
+					break;
+				}
 			}
 		}
+
+		if let Some(target_entity) = hit {
+			let impact = projectile_transform.translation.truncate();
+			damage_events.send(interaction::DamageEvent {
+				target: target_entity,
+				damage: *damage,
+				source: Some(*projectile_source),
+				impact: Some(impact),
+				direct_hit: true,
+			});
+			if blast.radius > 0.0 {
+				detonation_events.send(interaction::DetonationEvent {
+					translation: impact,
+					damage: *damage,
+					blast: *blast,
+					faction: *projectile_faction,
+					source: Some(*projectile_source),
+					direct_hit: Some(target_entity),
+				});
+			}
+			commands.entity(projectile_entity).despawn(); // Projectile is spent
+		}
+	}
+}
+
+/// Detonates projectiles whose Fuse runs out, or that pass close enough to a hostile ship
+pub fn projectile_fuse_system(
+	mut commands: Commands,
+	faction_definition_list: Res<faction_list::FactionDefinitionList>,
+	mut detonation_events: EventWriter<interaction::DetonationEvent>,
+	mut projectile_query: Query<(
+		Entity,
+		&mut projectile::Fuse,
+		&interaction::Damage,
+		&interaction::Blast,
+		&projectile::ProjectileSource,
+		&Transform,
+		&FactionName,
+	)>,
+	target_query: Query<(&Transform, &Collider, &FactionName), With<ship::Health>>,
+) {
+	let timestep = SIMULATION_TIMESTEP as f32;
+	for (
+		projectile_entity,
+		mut fuse,
+		damage,
+		blast,
+		projectile_source,
+		transform,
+		projectile_faction,
+	) in projectile_query.iter_mut()
+	{
+		if fuse.detonated {
+			continue;
+		}
+		let position = transform.translation.truncate();
+		let mut detonate = false;
+
+		if let Some(seconds_remaining) = fuse.seconds_remaining.as_mut() {
+			*seconds_remaining -= timestep;
+			detonate = *seconds_remaining <= 0.0;
+		}
+		if let Some(proximity_distance) = fuse.proximity_distance {
+			detonate |= target_query
+				.iter()
+				.filter(|(_, _, target_faction)| {
+					faction_definition_list.is_hostile(*projectile_faction, **target_faction)
+				})
+				.any(|(target_transform, target_collider, _)| {
+					distance_to_collider(position, target_transform.translation, target_collider)
+						<= proximity_distance
+				});
+		}
+
+		if detonate {
+			fuse.detonated = true;
+			detonation_events.send(interaction::DetonationEvent {
+				translation: position,
+				damage: *damage,
+				blast: *blast,
+				faction: *projectile_faction,
+				source: Some(*projectile_source),
+				direct_hit: None,
+			});
+			commands.entity(projectile_entity).despawn();
+		}
+	}
+}
+
+/// Distance from ```point``` to the nearest edge of a Collider at ```translation```, 0 if inside it
+pub fn distance_to_collider(point: Vec2, translation: Vec3, collider: &Collider) -> f32 {
+	let offset = (point - translation.truncate()).abs() - collider.0 / 2.0;
+	return offset.max(Vec2::ZERO).length();
+}
+
+/// True if the segment from ```start``` to ```end``` passes through a box of ```size```,
+/// centered on ```translation``` and turned by ```rotation```
+pub fn segment_intersects_box(
+	start: Vec2,
+	end: Vec2,
+	translation: Vec3,
+	rotation: Quat,
+	size: Vec2,
+) -> bool {
+	// Work in the box's frame, where it is axis aligned
+	let inverse_rotation = rotation.inverse();
+	let local_start = (inverse_rotation * (start.extend(0.0) - translation)).truncate();
+	let local_end = (inverse_rotation * (end.extend(0.0) - translation)).truncate();
+	let delta = local_end - local_start;
+	let half_size = size / 2.0;
+
+	// Clip the segment against each pair of box sides in turn
+	let mut t_enter = 0.0_f32;
+	let mut t_exit = 1.0_f32;
+	for axis in 0..2 {
+		if delta[axis].abs() <= f32::EPSILON {
+			if local_start[axis].abs() > half_size[axis] {
+				return false; // Parallel to and outside these sides
+			}
+			continue;
+		}
+		let t_a = (-half_size[axis] - local_start[axis]) / delta[axis];
+		let t_b = (half_size[axis] - local_start[axis]) / delta[axis];
+		t_enter = t_enter.max(t_a.min(t_b));
+		t_exit = t_exit.min(t_a.max(t_b));
+		if t_enter > t_exit {
+			return false;
+		}
+	}
+	return true;
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// A box 20 wide and 10 tall before it is turned
+	const BOX_SIZE: [f32; 2] = [20.0, 10.0];
+
+	/// Checks a segment against a box at the origin turned by ```rotation```
+	fn intersects_box(start: Vec2, end: Vec2, rotation: Quat) -> bool {
+		return segment_intersects_box(start, end, Vec3::ZERO, rotation, Vec2::from(BOX_SIZE));
+	}
+
+	#[test]
+	fn segment_through_box_hits() {
+		assert!(intersects_box(
+			Vec2::new(-30.0, 1.0),
+			Vec2::new(30.0, -1.0),
+			Quat::IDENTITY
+		));
+	}
+
+	#[test]
+	fn segment_past_box_misses() {
+		assert!(!intersects_box(
+			Vec2::new(-30.0, 20.0),
+			Vec2::new(30.0, 8.0),
+			Quat::IDENTITY
+		));
+		// Pointed at the box, but stops short of it
+		assert!(!intersects_box(
+			Vec2::new(-30.0, 0.0),
+			Vec2::new(-15.0, 0.0),
+			Quat::IDENTITY
+		));
+	}
+
+	#[test]
+	fn segment_parallel_to_edge() {
+		// Runs alongside the top edge, just outside and just inside
+		assert!(!intersects_box(
+			Vec2::new(-30.0, 6.0),
+			Vec2::new(30.0, 6.0),
+			Quat::IDENTITY
+		));
+		assert!(intersects_box(
+			Vec2::new(-30.0, 4.0),
+			Vec2::new(30.0, 4.0),
+			Quat::IDENTITY
+		));
+		// Runs along the edge itself
+		assert!(intersects_box(
+			Vec2::new(-30.0, 5.0),
+			Vec2::new(30.0, 5.0),
+			Quat::IDENTITY
+		));
+	}
+
+	#[test]
+	fn segment_ending_inside_box_hits() {
+		assert!(intersects_box(
+			Vec2::new(-30.0, 0.0),
+			Vec2::new(0.0, 0.0),
+			Quat::IDENTITY
+		));
+		assert!(intersects_box(
+			Vec2::new(1.0, 1.0),
+			Vec2::new(-1.0, -1.0),
+			Quat::IDENTITY
+		));
+	}
+
+	#[test]
+	fn segment_checked_against_turned_box() {
+		let quarter_turn = Quat::from_rotation_z(std::f32::consts::FRAC_PI_2);
+		// Turned a quarter, the box is 10 wide and 20 tall
+		assert!(intersects_box(
+			Vec2::new(-30.0, 8.0),
+			Vec2::new(30.0, 8.0),
+			quarter_turn
+		));
+		assert!(!intersects_box(
+			Vec2::new(-8.0, -30.0),
+			Vec2::new(-8.0, 30.0),
+			quarter_turn
+		));
 	}
 }
@@ -16,19 +16,44 @@ impl Default for ProjectileGuidance {
 	}
 }
 
+/// Marks a projectile point defence turrets can target, like a missile
+#[derive(Component, Clone, Default, Debug)]
+pub struct Targetable;
+
+/// The gun and cartridge a projectile was fired from
+#[derive(Component, Clone, Copy, Default, Debug)]
+pub struct ProjectileSource {
+	pub gun_name: gun_list::GunName,
+	pub projectile_name: cartridge_list::ProjectileName,
+	/// Ship that fired the projectile, which it never hits
+	pub ship: Option<Entity>,
+}
+
+/// Detonates the projectile before it hits anything, see ```physics::projectile_fuse_system```
+#[derive(Component, Clone, Copy, Default, Debug)]
+pub struct Fuse {
+	/// Detonates once a hostile ship is this close, in meters
+	pub proximity_distance: Option<f32>,
+	/// Detonates once this runs out
+	pub seconds_remaining: Option<f32>,
+	pub detonated: bool,
+}
+
 #[derive(Bundle, Default, Debug)]
 pub struct ProjectileBundle {
 	pub is_projectile: projectile::IsProjectile,
+	pub source: ProjectileSource,
 	pub guidance: ProjectileGuidance,
 	pub damage: interaction::Damage,
-	pub iff: interaction::IFF,
+	pub blast: interaction::Blast,
+	pub fuse: Fuse,
+	pub faction: faction_list::FactionName,
 
 	pub transform: Transform,
 	pub global_transform: GlobalTransform,
 	pub velocity: physics::Velocity,
 	pub acceleration: physics::Acceleration,
+	pub collider: physics::Collider,
 
-	pub sprite: Sprite,
-	pub texture: Handle<Image>,
-	pub visibility: Visibility,
+	pub appearance: Appearance,
 }
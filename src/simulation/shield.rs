@@ -0,0 +1,138 @@
+use super::*;
+
+/// Shield recharging
+pub struct ShieldPlugin;
+
+impl Plugin for ShieldPlugin {
+	fn build(&self, app: &mut App) {
+		app.add_simulation_system_set(
+			SimulationStep::Update,
+			SystemSet::new()
+				.after(SimulationSystem::Damage)
+				.before(SimulationSystem::Cleanup)
+				.with_system(shield_recharge_system),
+		);
+	}
+}
+
+/// How much shield strength a point of each damage type drains.
+/// Damage types with a multiplier of 0 pass straight through the shield.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug)]
+pub struct ShieldDamageMultipliers {
+	pub kinetic: f32,
+	pub explosive: f32,
+	pub energy: f32,
+}
+
+impl ShieldDamageMultipliers {
+	/// Multiplier for ```damage_type```
+	pub fn against(&self, damage_type: interaction::DamageType) -> f32 {
+		match damage_type {
+			interaction::DamageType::Kinetic => self.kinetic,
+			interaction::DamageType::Explosive => self.explosive,
+			interaction::DamageType::Energy => self.energy,
+		}
+	}
+}
+
+impl Default for ShieldDamageMultipliers {
+	fn default() -> Self {
+		Self {
+			kinetic: 1.0,
+			explosive: 1.0,
+			energy: 1.0,
+		}
+	}
+}
+
+/// Shield definition for storing a shield's parameters in Ship and Upgrade Definitions
+#[derive(Clone, Copy, Default, Serialize, Deserialize, Debug)]
+pub struct ShieldDefinition {
+	/// Strength of a fully charged shield
+	pub capacity: f32,
+	/// Strength regained per second while recharging
+	pub recharge_rate: f32,
+	/// Seconds after the last hit before the shield starts recharging
+	pub recharge_delay_seconds: f32,
+	pub damage_multipliers: ShieldDamageMultipliers,
+}
+
+// ==========
+// Components
+
+/// Absorbs damage before it reaches Health. Ships without a shield have a capacity of 0.
+#[derive(Component, Clone, Copy, Default, Debug)]
+pub struct Shield {
+	pub definition: ShieldDefinition,
+	pub strength: f32,
+	pub seconds_since_hit: f32,
+}
+
+impl Shield {
+	/// A fully charged shield
+	pub fn new(definition: ShieldDefinition) -> Self {
+		Self {
+			definition,
+			strength: definition.capacity,
+			seconds_since_hit: 0.0,
+		}
+	}
+
+	pub fn is_up(&self) -> bool {
+		self.strength > 0.0
+	}
+
+	/// Fraction (0 to 1) of its capacity the shield is charged to
+	pub fn fraction(&self) -> f32 {
+		if self.definition.capacity <= 0.0 {
+			return 0.0;
+		}
+		return (self.strength / self.definition.capacity).clamp(0.0, 1.0);
+	}
+
+	/// Drains the shield by ```damage```, returning the damage it couldn't stop
+	pub fn absorb(&mut self, damage: f32, damage_type: interaction::DamageType) -> f32 {
+		if self.definition.capacity <= 0.0 {
+			return damage;
+		}
+		self.seconds_since_hit = 0.0;
+
+		let multiplier = self.definition.damage_multipliers.against(damage_type);
+		if !self.is_up() || multiplier <= 0.0 {
+			return damage;
+		}
+		let shield_damage = damage * multiplier;
+		let absorbed = shield_damage.min(self.strength);
+		self.strength -= absorbed;
+		return (shield_damage - absorbed) / multiplier;
+	}
+}
+
+// ==========
+// Events
+
+/// Sent when a hit knocks a shield down
+#[derive(Debug)]
+pub struct ShieldDownEvent {
+	pub entity: Entity,
+}
+
+// ==========
+// Systems
+
+/// Recharges shields that haven't been hit for their recharge delay
+pub fn shield_recharge_system(mut shields: Query<&mut Shield>) {
+	let timestep = SIMULATION_TIMESTEP as f32;
+	for mut shield in shields.iter_mut() {
+		if shield.definition.capacity <= 0.0 {
+			continue;
+		}
+		shield.seconds_since_hit += timestep;
+		if shield.seconds_since_hit >= shield.definition.recharge_delay_seconds
+			&& shield.strength < shield.definition.capacity
+		{
+			shield.strength = (shield.strength + shield.definition.recharge_rate * timestep)
+				.min(shield.definition.capacity);
+		}
+	}
+}
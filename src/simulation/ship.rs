@@ -5,15 +5,28 @@ const BASE_AUDIO_PATH_GUNS: &str = "audio/sounds/guns/";
 #[derive(Component)]
 pub struct IsPlayerShip;
 
+#[derive(Component, Default)]
+pub struct IsEnemyShip;
+
+/// Marks a ship that has been destroyed but is kept around, like the player ship after game over
+#[derive(Component)]
+pub struct IsDestroyed;
+
 #[derive(Component, Clone, Copy, Deref, DerefMut, Serialize, Deserialize, Debug)]
 pub struct Health(pub f32);
 
+/// Health the ship started with
+#[derive(Component, Clone, Copy, Deref, DerefMut, Serialize, Deserialize, Debug)]
+pub struct MaxHealth(pub f32);
+
 #[derive(Debug)]
 pub struct ShipTurretMount {
 	pub mount_size: ItemSize,
 	pub mount_transform: Transform,
 	pub mount_field_of_view_degrees: f32,
 	pub mount_turret_entity: Option<Entity>,
+	/// Index of the Hull Section the mount sits on, if the ship has any
+	pub mount_section: Option<usize>,
 }
 
 #[derive(Component, Deref, DerefMut, Debug)]
@@ -24,6 +37,7 @@ pub struct ShipTurretMountList(pub Vec<ShipTurretMount>);
 pub struct ShipTurretAssignmentList(pub Option<Vec<turret::TurretBundle>>);
 
 impl ShipTurretAssignmentList {
+	#[allow(clippy::new_ret_no_self)]
 	pub fn new(&mut self) {
 		*self = ShipTurretAssignmentList(Some(Vec::new()));
 	}
@@ -38,7 +52,11 @@ impl ShipTurretAssignmentList {
 #[derive(Bundle)]
 pub struct ShipBundle {
 	pub health: Health,
-	pub iff: interaction::IFF,
+	pub max_health: MaxHealth,
+	pub armor: interaction::Armor,
+	pub shield: shield::Shield,
+	pub hull_sections: hull::HullSections,
+	pub faction: faction_list::FactionName,
 	pub turret_mount_list: ShipTurretMountList,
 	pub turret_assignment_list: ShipTurretAssignmentList,
 
@@ -46,17 +64,23 @@ pub struct ShipBundle {
 	pub global_transform: GlobalTransform,
 	pub velocity: physics::Velocity,
 	pub acceleration: physics::Acceleration,
+	pub max_speed: physics::MaxSpeed,
+	pub collider: physics::Collider,
+	pub acceleration_history: targeting::AccelerationHistory,
 
-	pub sprite: Sprite,
-	pub texture: Handle<Image>,
-	pub visibility: Visibility,
+	pub appearance: Appearance,
+	pub destruction_effect: destruction::DestructionEffect,
 }
 
 impl Default for ShipBundle {
 	fn default() -> Self {
 		Self {
 			health: Health(1.0),
-			iff: interaction::IFF::Neutral,
+			max_health: MaxHealth(1.0),
+			armor: default(),
+			shield: default(),
+			hull_sections: default(),
+			faction: faction_list::FactionName::Neutral,
 			turret_mount_list: ShipTurretMountList(Vec::new()),
 			turret_assignment_list: ShipTurretAssignmentList(None),
 
@@ -64,21 +88,49 @@ impl Default for ShipBundle {
 			global_transform: Default::default(),
 			velocity: physics::Velocity(Vec2::new(0.0, 0.0)),
 			acceleration: physics::Acceleration(Vec2::new(0.0, 0.0)),
+			max_speed: physics::MaxSpeed(0.0),
+			collider: default(),
+			acceleration_history: default(),
 
-			sprite: Default::default(),
-			texture: bevy::render::texture::DEFAULT_IMAGE_HANDLE.typed(),
-			visibility: Default::default(),
+			appearance: Default::default(),
+			destruction_effect: default(),
 		}
 	}
 }
 
+/// A ship with the extra components enemies need
+#[derive(Bundle)]
+pub struct EnemyShipBundle {
+	#[bundle]
+	pub ship: ShipBundle,
+	pub is_enemy_ship: IsEnemyShip,
+	pub behaviour: enemy::EnemyBehaviour,
+	pub flee_when_damaged: enemy::FleeWhenDamaged,
+	pub max_acceleration: physics::MaxAcceleration,
+}
+
+/// A ship with the extra components the player's escorts need
+#[derive(Bundle)]
+pub struct EscortShipBundle {
+	#[bundle]
+	pub ship: ShipBundle,
+	pub is_escort_ship: escort::IsEscortShip,
+	pub station: escort::EscortStation,
+	pub order: escort::EscortOrder,
+	pub priority_target: targeting::PriorityTarget,
+	pub max_acceleration: physics::MaxAcceleration,
+}
+
 impl ShipBundle {
+	/// Adds a turret for mount ```mount_number``` to the ship's Turret Assignment List.
+	/// ```ammunition``` is a cartridge to load instead of the gun's own.
 	pub fn generate_turret(
 		mut self,
-		asset_server: &Res<AssetServer>,
 		mount_number: usize,
 		gun_name: gun_list::GunName,
 		turret_num_barrels: turret::TurretNumBarrels,
+		ammunition: Option<cartridge_list::ProjectileName>,
+		turret_role: turret::TurretRole,
 		gun_definition_list: &Res<gun_list::GunDefinitionList>,
 		cartridge_definition_list: &Res<cartridge_list::CartridgeDefinitionList>,
 	) -> Self {
@@ -94,46 +146,49 @@ impl ShipBundle {
 			),
 		};
 
-		let (turret_texture, turret_texture_size) =
-			turret::lookup_turret_texture(asset_server, gun_name, mount_size, turret_num_barrels);
+		let projectile_name = ammunition.unwrap_or(gun_definition.projectile_name);
+
+		let (turret_texture_path, turret_texture_size) =
+			turret::lookup_turret_texture(gun_name, mount_size, turret_num_barrels);
 
 		// Build turret
 		let turret_bundle = turret::TurretBundle {
 			turret_size: mount_size,
 			turret_mount_number: turret::TurretMountNumber(mount_number),
+			turret_role,
 			turret_properties: self.generate_turret_properties(mount_number),
+			health: Health(turret::turret_max_health(mount_size)),
+			max_health: MaxHealth(turret::turret_max_health(mount_size)),
+			collider: physics::Collider(turret_texture_size),
 			transform: self.turret_mount_list[mount_number].mount_transform,
 			gun_properties: gun::GunProperties {
+				gun_name,
 				gun_type: gun_definition.gun_type,
 				gun_size: gun_definition.gun_size,
 				rate_of_fire: gun_definition.rate_of_fire,
 
 				cartridge_data: match cartridge_definition_list.iter().find(
-					|&cartridge_definition| {
-						cartridge_definition.projectile_name == gun_definition.projectile_name
-					},
+					|&cartridge_definition| cartridge_definition.projectile_name == projectile_name,
 				) {
 					Some(val) => val.clone(),
 					None => panic!(
 						"Failed to find cartridge named {:?} in cartridge definitions list!",
-						gun_definition.projectile_name
+						projectile_name
 					),
 				},
 
-				fire_sound: asset_server
-					.load(&(BASE_AUDIO_PATH_GUNS.to_string() + &gun_definition.fire_sound_path)),
+				fire_sound_path: BASE_AUDIO_PATH_GUNS.to_string() + &gun_definition.fire_sound_path,
 			},
 			gun_assignment_list: turret::generate_turret_gun_list(
-				asset_server,
 				mount_size,
 				turret_num_barrels,
 				&gun_definition.texture_path,
 			),
-			sprite: Sprite {
-				custom_size: Some(turret_texture_size),
+			appearance: Appearance {
+				texture_path: Some(turret_texture_path),
+				size: turret_texture_size,
 				..default()
 			},
-			texture: turret_texture,
 			..default()
 		};
 
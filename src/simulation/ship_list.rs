@@ -2,10 +2,16 @@ use super::*;
 
 const SHIP_DATA_PATH: &str = "data/ships/";
 
-#[derive(PartialEq, Serialize, Deserialize, Debug)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Debug)]
 pub enum ShipName {
 	TemplateShip,
 	PlayerTempShip,
+	EnemyDrone,
+	EnemyGunship,
+	EscortCorvette,
+	StrongholdCore,
+	StrongholdBattery,
+	StrongholdShieldGenerator,
 }
 
 /// Turret Mount Definition for storing turret mount data in Ship Definitions
@@ -22,10 +28,22 @@ pub struct TurretMountDefinition {
 pub struct ShipDefinition {
 	pub ship_name: ShipName,
 	pub health: ship::Health,
-	pub iff: interaction::IFF,
+	/// Hull armor against each damage type
+	pub armor: interaction::Armor,
+	/// Shield the hull comes with, if any
+	pub shield: Option<shield::ShieldDefinition>,
+	/// Faction the hull flies for, unless whatever spawns it assigns another
+	pub faction: faction_list::FactionName,
+	pub max_speed: physics::MaxSpeed,
 	pub texture_path: String,
 	pub texture_scale: Vec2,
+	/// Size of the hull's bounding box for collisions
+	pub collider_size: Vec2,
 	pub turret_mounts: Vec<TurretMountDefinition>,
+	/// Sections that take damage on their own. Mounts on a destroyed section are disabled.
+	pub hull_sections: Vec<hull::HullSectionDefinition>,
+	/// Explosion, sound and debris when the ship is destroyed
+	pub destruction_effect: destruction::DestructionEffect,
 }
 
 /// List of Ship Definitions
@@ -39,9 +57,22 @@ pub fn write_ship_definition_template() {
 	let ship_definition_template = ShipDefinition {
 		ship_name: ShipName::TemplateShip,
 		health: ship::Health(100.0),
-		iff: interaction::IFF::Friendly,
+		armor: interaction::Armor {
+			kinetic: 1.0,
+			explosive: 0.5,
+			energy: 0.0,
+		},
+		shield: Some(shield::ShieldDefinition {
+			capacity: 20.0,
+			recharge_rate: 2.0,
+			recharge_delay_seconds: 3.0,
+			damage_multipliers: default(),
+		}),
+		faction: faction_list::FactionName::Player,
+		max_speed: physics::MaxSpeed(50.0),
 		texture_path: "template_texture.png".to_string(),
 		texture_scale: Vec2::new(200.0, 200.0),
+		collider_size: Vec2::new(150.0, 180.0),
 		turret_mounts: vec![
 			TurretMountDefinition {
 				size: ItemSize::Small,
@@ -62,6 +93,34 @@ pub fn write_ship_definition_template() {
 				field_of_view_degrees: 90.0,
 			},
 		],
+		hull_sections: vec![
+			hull::HullSectionDefinition {
+				section_name: hull::HullSectionName::Bow,
+				health: 30.0,
+				translation: Vec2::new(0.0, 60.0),
+			},
+			hull::HullSectionDefinition {
+				section_name: hull::HullSectionName::Midship,
+				health: 50.0,
+				translation: Vec2::new(0.0, 0.0),
+			},
+			hull::HullSectionDefinition {
+				section_name: hull::HullSectionName::Stern,
+				health: 30.0,
+				translation: Vec2::new(0.0, -50.0),
+			},
+			hull::HullSectionDefinition {
+				section_name: hull::HullSectionName::Engines,
+				health: 20.0,
+				translation: Vec2::new(0.0, -80.0),
+			},
+		],
+		destruction_effect: destruction::DestructionEffect {
+			explosion_texture_path: Some("template_explosion.png".to_string()),
+			explosion_sound_path: Some("template_explosion.ogg".to_string()),
+			debris_count: 6,
+			..default()
+		},
 	};
 	// Write out template
 	crate::game_io::write_definition_template(SHIP_DATA_PATH, ship_definition_template);
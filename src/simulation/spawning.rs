@@ -1,80 +1,274 @@
 use super::*;
+use rand::Rng;
 
 const BASE_TEXTURES_PATH_SHIPS: &str = "textures/ships/";
+const BASE_TEXTURES_PATH_EFFECTS: &str = "textures/effects/";
+const BASE_AUDIO_PATH_EXPLOSIONS: &str = "audio/sounds/explosions/";
+/// Where the player ship starts the battle
+pub const PLAYER_SPAWN_TRANSLATION: [f32; 3] = [0.0, -500.0, 0.0];
+
+/// Spawns the player ship, enemy waves, and the child turrets and guns of new ships
+pub struct SpawningPlugin;
+
+impl Plugin for SpawningPlugin {
+	fn build(&self, app: &mut App) {
+		app.init_resource::<PlayerLoadout>()
+			.init_resource::<PlayerEscortGroup>()
+			.init_resource::<DifficultyCurve>()
+			.init_resource::<wave::WaveDirector>()
+			.add_startup_system(spawn_player_ship)
+			.add_startup_system(spawn_player_escorts.after(spawn_player_ship))
+			.add_simulation_system_set(
+				SimulationStep::Spawn,
+				SystemSet::new()
+					.with_system(wave::wave_member_departure_system)
+					.with_system(wave::wave_director_system),
+			)
+			.add_simulation_system_set(
+				SimulationStep::SpawnTurrets,
+				SystemSet::new().with_system(turret::ship_turret_spawn_system),
+			)
+			.add_simulation_system_set(
+				SimulationStep::SpawnGuns,
+				SystemSet::new().with_system(gun_list::turret_gun_spawn_system),
+			);
+	}
+}
+
+/// Loadout the player ship is spawned with
+#[derive(Deref, DerefMut, Default, Debug)]
+pub struct PlayerLoadout(pub loadout_list::LoadoutName);
+
+/// Escort group that accompanies the player ship
+#[derive(Deref, DerefMut, Default, Debug)]
+pub struct PlayerEscortGroup(pub escort_list::EscortGroupName);
+
+/// Difficulty curve enemies are scaled by as the run goes on
+#[derive(Deref, DerefMut, Default, Debug)]
+pub struct DifficultyCurve(pub difficulty_list::DifficultyCurveName);
 
 pub fn spawn_player_ship(
 	mut commands: Commands,
-	asset_server: Res<AssetServer>,
+	player_loadout: Res<PlayerLoadout>,
+	current_level: Res<level::CurrentLevel>,
+	level_definition_list: Res<level_list::LevelDefinitionList>,
+	loadout_definition_list: Res<loadout_list::LoadoutDefinitionList>,
+	upgrade_definition_list: Res<upgrade_list::UpgradeDefinitionList>,
 	ship_definition_list: Res<ship_list::ShipDefinitionList>,
 	gun_definition_list: Res<gun_list::GunDefinitionList>,
 	cartridge_definition_list: Res<cartridge_list::CartridgeDefinitionList>,
 ) {
-	let spawn_transform = Transform::from_xyz(0.0, -500.0, 0.0);
+	let spawn_transform = Transform::from_translation(Vec3::from(PLAYER_SPAWN_TRANSLATION));
 
-	let player_ship = spawn_ship(
-		ship_list::ShipName::PlayerTempShip,
+	let player_ship = spawn_loadout(
+		player_loadout.0,
 		spawn_transform,
-		&asset_server,
+		&loadout_definition_list,
+		&upgrade_definition_list,
 		&ship_definition_list,
-	)
-	.generate_turret( // Nose
-		&asset_server,
-		0,
-		gun_list::GunName::SmallMachinegun,
-		turret::TurretNumBarrels::Double,
-		&gun_definition_list,
-		&cartridge_definition_list,
-	)
-	/* .generate_turret( // Front Left
-		&asset_server,
-		1,
-		gun_list::GunName::SmallMachinegun,
-		turret::TurretNumBarrels::Single,
 		&gun_definition_list,
 		&cartridge_definition_list,
-	)
-	.generate_turret( // Front Right
-		&asset_server,
-		2,
-		gun_list::GunName::SmallMachinegun,
-		turret::TurretNumBarrels::Single,
-		&gun_definition_list,
-		&cartridge_definition_list,
-	)
-	.generate_turret( // Rear Left
-		&asset_server,
-		3,
-		gun_list::GunName::SmallMachinegun,
-		turret::TurretNumBarrels::Double,
-		&gun_definition_list,
-		&cartridge_definition_list,
-	)
-	.generate_turret( // Rear Right
-		&asset_server,
-		4,
-		gun_list::GunName::SmallMachinegun,
-		turret::TurretNumBarrels::Double,
-		&gun_definition_list,
-		&cartridge_definition_list,
-	)
-	.generate_turret( // Tail
-		&asset_server,
-		5,
-		gun_list::GunName::SmallMachinegun,
-		turret::TurretNumBarrels::Triple,
-		&gun_definition_list,
-		&cartridge_definition_list,
-	) */;
+	);
 
 	commands
 		.spawn_bundle(player_ship)
-		.insert(ship::IsPlayerShip);
+		.insert(ship::IsPlayerShip)
+		.insert(level::starting_throttle(
+			current_level.0,
+			&level_definition_list,
+		));
+}
+
+/// Spawns the player's escorts on their stations around the player ship's spawn point
+pub fn spawn_player_escorts(
+	mut commands: Commands,
+	player_escort_group: Res<PlayerEscortGroup>,
+	escort_group_definition_list: Res<escort_list::EscortGroupDefinitionList>,
+	loadout_definition_list: Res<loadout_list::LoadoutDefinitionList>,
+	upgrade_definition_list: Res<upgrade_list::UpgradeDefinitionList>,
+	ship_definition_list: Res<ship_list::ShipDefinitionList>,
+	gun_definition_list: Res<gun_list::GunDefinitionList>,
+	cartridge_definition_list: Res<cartridge_list::CartridgeDefinitionList>,
+) {
+	let escort_group_definition =
+		match escort_group_definition_list
+			.iter()
+			.find(|&escort_group_definition| {
+				escort_group_definition.escort_group_name == player_escort_group.0
+			}) {
+			Some(val) => val,
+			None => panic!(
+				"Failed to find {:?} in Escort Group definitions list!",
+				player_escort_group.0
+			),
+		};
+
+	for escort_definition in escort_group_definition.escorts.iter() {
+		let mut escort_ship = spawn_loadout(
+			escort_definition.loadout_name,
+			Transform::from_translation(
+				Vec3::from(PLAYER_SPAWN_TRANSLATION) + escort_definition.station.extend(0.0),
+			),
+			&loadout_definition_list,
+			&upgrade_definition_list,
+			&ship_definition_list,
+			&gun_definition_list,
+			&cartridge_definition_list,
+		);
+		escort_ship.faction = faction_list::PLAYER_FACTION;
+
+		commands.spawn_bundle(ship::EscortShipBundle {
+			ship: escort_ship,
+			is_escort_ship: escort::IsEscortShip,
+			station: escort::EscortStation(escort_definition.station),
+			order: escort::EscortOrder::Screen,
+			priority_target: targeting::PriorityTarget(None),
+			max_acceleration: escort_definition.acceleration,
+		});
+	}
+}
+
+/// Builds a ship from a Loadout Definition, with its turrets queued for spawning and its upgrades fitted
+pub fn spawn_loadout(
+	loadout_name: loadout_list::LoadoutName,
+	spawn_transform: Transform, // Translation and Rotation to spawn the ship at
+	loadout_definition_list: &Res<loadout_list::LoadoutDefinitionList>,
+	upgrade_definition_list: &Res<upgrade_list::UpgradeDefinitionList>,
+	ship_definition_list: &Res<ship_list::ShipDefinitionList>,
+	gun_definition_list: &Res<gun_list::GunDefinitionList>,
+	cartridge_definition_list: &Res<cartridge_list::CartridgeDefinitionList>,
+) -> ship::ShipBundle {
+	let loadout_definition = match loadout_definition_list
+		.iter()
+		.find(|&loadout_definition| loadout_definition.loadout_name == loadout_name)
+	{
+		Some(val) => val,
+		None => panic!(
+			"Failed to find {:?} in Loadout definitions list!",
+			loadout_name
+		),
+	};
+
+	let mut ship_bundle = spawn_ship(
+		loadout_definition.ship_name,
+		spawn_transform,
+		ship_definition_list,
+	);
+	for turret_loadout in loadout_definition.turrets.iter() {
+		ship_bundle = ship_bundle.generate_turret(
+			turret_loadout.mount_number,
+			turret_loadout.gun_name,
+			turret_loadout.num_barrels,
+			turret_loadout.ammunition,
+			turret_loadout.role,
+			gun_definition_list,
+			cartridge_definition_list,
+		);
+	}
+
+	let mut fitted_categories = Vec::new();
+	for &upgrade_name in loadout_definition.upgrades.iter() {
+		let upgrade_definition = match upgrade_definition_list
+			.iter()
+			.find(|&upgrade_definition| upgrade_definition.upgrade_name == upgrade_name)
+		{
+			Some(val) => val,
+			None => panic!(
+				"Failed to find {:?} in Upgrade definitions list!",
+				upgrade_name
+			),
+		};
+		let category = upgrade_definition.effect.category();
+		assert!(
+			!fitted_categories.contains(&category),
+			"{:?} fits more than one {:?} upgrade!",
+			loadout_name,
+			category
+		);
+		fitted_categories.push(category);
+		upgrade_definition.effect.apply(&mut ship_bundle);
+	}
+
+	return ship_bundle;
+}
+
+/// Builds an enemy ship from an Enemy Definition, with its turrets queued for spawning
+pub fn spawn_enemy(
+	enemy_name: enemy_list::EnemyName,
+	spawn_transform: Transform, // Translation and Rotation to spawn the enemy at
+	enemy_definition_list: &Res<enemy_list::EnemyDefinitionList>,
+	ship_definition_list: &Res<ship_list::ShipDefinitionList>,
+	gun_definition_list: &Res<gun_list::GunDefinitionList>,
+	cartridge_definition_list: &Res<cartridge_list::CartridgeDefinitionList>,
+) -> ship::EnemyShipBundle {
+	let enemy_definition = match enemy_definition_list
+		.iter()
+		.find(|&enemy_definition| enemy_definition.enemy_name == enemy_name)
+	{
+		Some(val) => val,
+		None => panic!("Failed to find {:?} in Enemy definitions list!", enemy_name),
+	};
+
+	let mut ship_bundle = spawn_ship(
+		enemy_definition.ship_name,
+		spawn_transform,
+		ship_definition_list,
+	);
+	ship_bundle.health = enemy_definition.health;
+	ship_bundle.max_health = ship::MaxHealth(enemy_definition.health.0);
+	if let Some(speed) = enemy_definition.speed {
+		ship_bundle.max_speed = speed;
+	}
+	ship_bundle.faction = enemy_definition.faction;
+	for turret_loadout in enemy_definition.turrets.iter() {
+		ship_bundle = ship_bundle.generate_turret(
+			turret_loadout.mount_number,
+			turret_loadout.gun_name,
+			turret_loadout.num_barrels,
+			turret_loadout.ammunition,
+			turret_loadout.role,
+			gun_definition_list,
+			cartridge_definition_list,
+		);
+	}
+
+	return ship::EnemyShipBundle {
+		ship: ship_bundle,
+		is_enemy_ship: ship::IsEnemyShip,
+		behaviour: enemy_definition.behaviour,
+		flee_when_damaged: enemy::FleeWhenDamaged(enemy_definition.flee_health_fraction),
+		max_acceleration: enemy_definition.acceleration,
+	};
+}
+
+/// Rolls the Difficulty Level's substitutions for ```enemy_name```, returning the enemy to spawn instead
+pub fn substitute_enemy(
+	enemy_name: enemy_list::EnemyName,
+	difficulty_level: &difficulty_list::DifficultyLevel,
+	rng: &mut SimulationRng,
+) -> enemy_list::EnemyName {
+	for substitution in difficulty_level.substitutions.iter() {
+		if substitution.from == enemy_name && rng.gen::<f32>() < substitution.chance {
+			return substitution.to;
+		}
+	}
+	return enemy_name;
+}
+
+/// Scales an enemy's health and speed by the Difficulty Level
+pub fn apply_difficulty(
+	enemy: &mut ship::EnemyShipBundle,
+	difficulty_level: &difficulty_list::DifficultyLevel,
+) {
+	enemy.ship.health.0 *= difficulty_level.health_multiplier;
+	enemy.ship.max_health.0 *= difficulty_level.health_multiplier;
+	enemy.ship.max_speed.0 *= difficulty_level.speed_multiplier;
+	enemy.max_acceleration.0 *= difficulty_level.speed_multiplier;
 }
 
 pub fn spawn_ship(
 	ship_name: ship_list::ShipName,
 	spawn_transform: Transform, // Translation and Rotation to spawn the ship at
-	asset_server: &Res<AssetServer>,
 	ship_definition_list: &Res<ship_list::ShipDefinitionList>,
 ) -> ship::ShipBundle {
 	const TURRET_Z_OFFSET: f32 = 25.0;
@@ -85,19 +279,43 @@ pub fn spawn_ship(
 			// Found ship in list, fill the Ship Bundle
 			ship_bundle = Some(ship::ShipBundle {
 				health: ship_definition.health,
-				iff: ship_definition.iff,
+				max_health: ship::MaxHealth(ship_definition.health.0),
+				armor: ship_definition.armor,
+				hull_sections: hull::HullSections::new(&ship_definition.hull_sections),
+				shield: match ship_definition.shield {
+					Some(shield_definition) => shield::Shield::new(shield_definition),
+					None => default(),
+				},
+				max_speed: ship_definition.max_speed,
+				faction: ship_definition.faction,
 				transform: spawn_transform,
-				texture: asset_server
-					.load(&(BASE_TEXTURES_PATH_SHIPS.to_string() + &ship_definition.texture_path)),
-				sprite: Sprite {
-					custom_size: Some(ship_definition.texture_scale),
+				collider: physics::Collider(ship_definition.collider_size),
+				appearance: Appearance {
+					texture_path: Some(
+						BASE_TEXTURES_PATH_SHIPS.to_string() + &ship_definition.texture_path,
+					),
+					size: ship_definition.texture_scale,
 					..default()
 				},
+				destruction_effect: destruction::DestructionEffect {
+					explosion_texture_path: ship_definition
+						.destruction_effect
+						.explosion_texture_path
+						.as_ref()
+						.map(|path| BASE_TEXTURES_PATH_EFFECTS.to_string() + path),
+					explosion_sound_path: ship_definition
+						.destruction_effect
+						.explosion_sound_path
+						.as_ref()
+						.map(|path| BASE_AUDIO_PATH_EXPLOSIONS.to_string() + path),
+					..ship_definition.destruction_effect.clone()
+				},
 				..default()
 			});
 
-			// Turret Mounts
+			// Turret Mounts, each on the Hull Section nearest to it
 			let mut turret_mounts = ship::ShipTurretMountList(Vec::new());
+			let hull_sections = &ship_bundle.as_ref().unwrap().hull_sections;
 			for turret_mount in ship_definition.turret_mounts.iter() {
 				turret_mounts.push(ship::ShipTurretMount {
 					mount_size: turret_mount.size,
@@ -109,6 +327,7 @@ pub fn spawn_ship(
 					)),
 					mount_field_of_view_degrees: turret_mount.field_of_view_degrees,
 					mount_turret_entity: None,
+					mount_section: hull_sections.nearest_section(turret_mount.translation),
 				});
 			}
 			ship_bundle.as_mut().unwrap().turret_mount_list = turret_mounts;
@@ -126,30 +345,3 @@ pub fn spawn_ship(
 
 	return ship_bundle;
 }
-
-// ==========
-// Systems
-
-pub fn target_spawn_system(mut commands: Commands, time: Res<Time>, mut timer: ResMut<SpawnTimer>) {
-	if timer.tick(time.delta()).just_finished() {
-		commands
-			.spawn_bundle(SpriteBundle {
-				sprite: Sprite {
-					color: (Color::YELLOW),
-					..default()
-				},
-				transform: Transform {
-					translation: Vec3::new(rand::random::<f32>() * 600.0 - 300.0, 400.0, 0.0),
-					scale: Vec3::new(4.0, 4.0, 0.0),
-					..default()
-				},
-				..default()
-			})
-			.insert(ship::Health(10.0))
-			.insert(physics::Velocity(Vec2::new(
-				rand::random::<f32>() * 80.0 - 10.0,
-				rand::random::<f32>() * -80.0 - 20.0,
-			)))
-			.insert(interaction::IFF::Enemy);
-	}
-}
@@ -0,0 +1,109 @@
+use std::collections::{BTreeMap, HashMap};
+
+use super::*;
+
+/// Collects Battle Statistics for balance testing.
+/// Not part of ```SimulationPlugins```, add it where the numbers are wanted.
+pub struct StatisticsPlugin;
+
+impl Plugin for StatisticsPlugin {
+	fn build(&self, app: &mut App) {
+		app.init_resource::<BattleStatistics>()
+			.add_simulation_system_set(
+				SimulationStep::Update,
+				SystemSet::new()
+					.after(SimulationSystem::Cleanup)
+					.with_system(battle_statistics_system),
+			);
+	}
+}
+
+/// Running totals for a single battle
+#[derive(Default, Debug, PartialEq)]
+pub struct BattleStatistics {
+	/// Number of simulation ticks run so far
+	pub ticks: usize,
+	pub shots_fired: BTreeMap<gun_list::GunName, usize>,
+	/// Projectiles that hit something, by the gun that fired them
+	pub hits: BTreeMap<gun_list::GunName, usize>,
+	/// Damage of the hits and blast splash by each projectile, before shields and armor
+	pub damage_dealt: BTreeMap<cartridge_list::ProjectileName, f32>,
+	pub enemies_spawned: usize,
+	pub enemies_destroyed: usize,
+	/// Seconds each destroyed enemy lived for
+	pub times_to_kill_seconds: Vec<f32>,
+	/// Tick each living enemy was spawned on
+	pub enemy_spawn_ticks: HashMap<Entity, usize>,
+}
+
+impl BattleStatistics {
+	/// Simulated time in seconds
+	pub fn elapsed_seconds(&self) -> f32 {
+		return (self.ticks as f64 * SIMULATION_TIMESTEP) as f32;
+	}
+}
+
+// ==========
+// Systems
+
+/// Tallies this tick's shots, hits, damage and kills into the Battle Statistics
+pub fn battle_statistics_system(
+	mut battle_statistics: ResMut<BattleStatistics>,
+	mut gun_fired_events: EventReader<gun::GunFiredEvent>,
+	mut damage_events: EventReader<interaction::DamageEvent>,
+	mut destroyed_events: EventReader<interaction::DestroyedEvent>,
+	faction_definition_list: Res<faction_list::FactionDefinitionList>,
+	new_targets: Query<
+		(Entity, &faction_list::FactionName),
+		(Added<ship::Health>, Without<projectile::IsProjectile>),
+	>,
+) {
+	let current_tick = battle_statistics.ticks;
+
+	for (entity, faction) in new_targets.iter() {
+		if faction_definition_list.is_hostile(faction_list::PLAYER_FACTION, *faction) {
+			battle_statistics.enemies_spawned += 1;
+			battle_statistics
+				.enemy_spawn_ticks
+				.insert(entity, current_tick);
+		}
+	}
+
+	for gun_fired_event in gun_fired_events.iter() {
+		*battle_statistics
+			.shots_fired
+			.entry(gun_fired_event.gun_name)
+			.or_insert(0) += 1;
+	}
+
+	for damage_event in damage_events.iter() {
+		if let Some(source) = damage_event.source {
+			// Blast splash adds to the damage dealt, but isn't a hit
+			if damage_event.direct_hit {
+				*battle_statistics.hits.entry(source.gun_name).or_insert(0) += 1;
+			}
+			*battle_statistics
+				.damage_dealt
+				.entry(source.projectile_name)
+				.or_insert(0.0) += damage_event.damage.amount;
+		}
+	}
+
+	for destroyed_event in destroyed_events.iter() {
+		if !faction_definition_list
+			.is_hostile(faction_list::PLAYER_FACTION, destroyed_event.faction)
+		{
+			continue;
+		}
+		battle_statistics.enemies_destroyed += 1;
+		if let Some(spawn_tick) = battle_statistics
+			.enemy_spawn_ticks
+			.remove(&destroyed_event.entity)
+		{
+			let time_to_kill = ((current_tick - spawn_tick) as f64 * SIMULATION_TIMESTEP) as f32;
+			battle_statistics.times_to_kill_seconds.push(time_to_kill);
+		}
+	}
+
+	battle_statistics.ticks += 1;
+}
@@ -0,0 +1,299 @@
+use super::{stronghold_list::*, *};
+
+/// Spawns the stronghold at the end of the level and runs its shields, defenders and defeat
+pub struct StrongholdPlugin;
+
+impl Plugin for StrongholdPlugin {
+	fn build(&self, app: &mut App) {
+		app.add_simulation_system_set(
+			SimulationStep::Spawn,
+			SystemSet::new()
+				.with_system(stronghold_spawn_system.after(wave::wave_director_system))
+				.with_system(stronghold_defender_spawn_system.after(stronghold_spawn_system))
+				.with_system(stronghold_shield_system)
+				.with_system(stronghold_defeat_system.after(stronghold_defender_spawn_system)),
+		);
+	}
+}
+
+// ==========
+// Components
+
+/// Centre of a stronghold. Its sections are separate ships that drift along with it.
+#[derive(Component, Debug)]
+pub struct Stronghold {
+	pub stronghold_name: StrongholdName,
+	/// Seconds until each of the definition's defenders is next launched
+	pub defender_cooldowns: Vec<f32>,
+}
+
+/// A destructible section of a stronghold
+#[derive(Component, Clone, Copy, Debug)]
+pub struct StrongholdSection {
+	pub stronghold: Entity,
+	pub shield_generator: bool,
+}
+
+/// An enemy launched by a stronghold
+#[derive(Component, Clone, Copy, Debug)]
+pub struct StrongholdDefender {
+	pub stronghold: Entity,
+	/// Index into the stronghold definition's defenders
+	pub defender_index: usize,
+}
+
+// ==========
+// Events
+
+/// Sent when every section of the level's stronghold has been destroyed, ending the stage
+#[derive(Debug)]
+pub struct StageCompleteEvent {
+	pub level_name: level_list::LevelName,
+	pub stronghold_name: StrongholdName,
+}
+
+/// Builds the sections of a stronghold centred on ```stronghold_translation```,
+/// each a ship with its turrets queued for spawning
+pub fn spawn_stronghold_sections(
+	stronghold_definition: &StrongholdDefinition,
+	stronghold_translation: Vec3,
+	ship_definition_list: &Res<ship_list::ShipDefinitionList>,
+	gun_definition_list: &Res<gun_list::GunDefinitionList>,
+	cartridge_definition_list: &Res<cartridge_list::CartridgeDefinitionList>,
+) -> Vec<(ship::ShipBundle, bool)> {
+	let mut sections = Vec::new();
+	for section_definition in stronghold_definition.sections.iter() {
+		let section_transform = Transform::from_translation(
+			stronghold_translation + section_definition.translation.extend(0.0),
+		)
+		.with_rotation(Quat::from_rotation_z(
+			section_definition.rotation_degrees.to_radians(),
+		));
+
+		let mut section = spawning::spawn_ship(
+			section_definition.ship_name,
+			section_transform,
+			ship_definition_list,
+		);
+		section.health = section_definition.health;
+		section.max_health = ship::MaxHealth(section_definition.health.0);
+		section.faction = stronghold_definition.faction;
+		section.velocity = physics::Velocity(stronghold_definition.velocity);
+		section.max_speed = physics::MaxSpeed(stronghold_definition.velocity.length());
+		for turret_loadout in section_definition.turrets.iter() {
+			section = section.generate_turret(
+				turret_loadout.mount_number,
+				turret_loadout.gun_name,
+				turret_loadout.num_barrels,
+				turret_loadout.ammunition,
+				turret_loadout.role,
+				gun_definition_list,
+				cartridge_definition_list,
+			);
+		}
+		sections.push((section, section_definition.shield_generator));
+	}
+	return sections;
+}
+
+fn find_stronghold_definition(
+	stronghold_name: StrongholdName,
+	stronghold_definition_list: &StrongholdDefinitionList,
+) -> &StrongholdDefinition {
+	match stronghold_definition_list
+		.iter()
+		.find(|&stronghold_definition| stronghold_definition.stronghold_name == stronghold_name)
+	{
+		Some(val) => val,
+		None => panic!(
+			"Failed to find {:?} in Stronghold definitions list!",
+			stronghold_name
+		),
+	}
+}
+
+// ==========
+// Systems
+
+/// Spawns the level's stronghold ahead of the player ship when it reaches the end of the track
+pub fn stronghold_spawn_system(
+	mut commands: Commands,
+	mut level_progress: ResMut<level::LevelProgress>,
+	current_level: Res<level::CurrentLevel>,
+	level_definition_list: Res<level_list::LevelDefinitionList>,
+	stronghold_definition_list: Res<StrongholdDefinitionList>,
+	ship_definition_list: Res<ship_list::ShipDefinitionList>,
+	gun_definition_list: Res<gun_list::GunDefinitionList>,
+	cartridge_definition_list: Res<cartridge_list::CartridgeDefinitionList>,
+	player_ship: Query<&Transform, With<ship::IsPlayerShip>>,
+) {
+	if level_progress.arrived && !level_progress.stronghold_spawned {
+		level_progress.stronghold_spawned = true;
+
+		let level_definition = match level_definition_list
+			.iter()
+			.find(|&level_definition| level_definition.level_name == current_level.0)
+		{
+			Some(val) => val,
+			None => panic!(
+				"Failed to find {:?} in Level definitions list!",
+				current_level.0
+			),
+		};
+		let stronghold_definition = find_stronghold_definition(
+			level_definition.stronghold_name,
+			&stronghold_definition_list,
+		);
+
+		let player_translation = match player_ship.get_single() {
+			Ok(val) => val.translation,
+			Err(_) => Vec3::ZERO,
+		};
+		let stronghold_translation = Vec3::new(
+			player_translation.x,
+			player_translation.y + level_definition.stronghold_distance,
+			0.0,
+		);
+
+		let stronghold = commands
+			.spawn()
+			.insert(Stronghold {
+				stronghold_name: stronghold_definition.stronghold_name,
+				defender_cooldowns: stronghold_definition
+					.defenders
+					.iter()
+					.map(|defender| defender.interval_seconds)
+					.collect(),
+			})
+			.insert(stronghold_definition.faction)
+			.insert(physics::Velocity(stronghold_definition.velocity))
+			.insert_bundle(TransformBundle::from_transform(
+				Transform::from_translation(stronghold_translation),
+			))
+			.id();
+
+		for (section, shield_generator) in spawn_stronghold_sections(
+			stronghold_definition,
+			stronghold_translation,
+			&ship_definition_list,
+			&gun_definition_list,
+			&cartridge_definition_list,
+		) {
+			commands.spawn_bundle(section).insert(StrongholdSection {
+				stronghold,
+				shield_generator,
+			});
+		}
+	}
+}
+
+/// Launches each stronghold's defenders on their intervals, up to their limit alive at once
+pub fn stronghold_defender_spawn_system(
+	mut commands: Commands,
+	mut strongholds: Query<(Entity, &mut Stronghold, &Transform)>,
+	stronghold_definition_list: Res<StrongholdDefinitionList>,
+	enemy_definition_list: Res<enemy_list::EnemyDefinitionList>,
+	ship_definition_list: Res<ship_list::ShipDefinitionList>,
+	gun_definition_list: Res<gun_list::GunDefinitionList>,
+	cartridge_definition_list: Res<cartridge_list::CartridgeDefinitionList>,
+	defenders: Query<&StrongholdDefender>,
+) {
+	let timestep = SIMULATION_TIMESTEP as f32;
+	for (stronghold_entity, mut stronghold, stronghold_transform) in strongholds.iter_mut() {
+		let stronghold_definition =
+			find_stronghold_definition(stronghold.stronghold_name, &stronghold_definition_list);
+
+		for (defender_index, defender_definition) in
+			stronghold_definition.defenders.iter().enumerate()
+		{
+			stronghold.defender_cooldowns[defender_index] -= timestep;
+			if stronghold.defender_cooldowns[defender_index] > 0.0 {
+				continue;
+			}
+			stronghold.defender_cooldowns[defender_index] += defender_definition.interval_seconds;
+
+			let num_alive = defenders
+				.iter()
+				.filter(|defender| {
+					defender.stronghold == stronghold_entity
+						&& defender.defender_index == defender_index
+				})
+				.count();
+			if num_alive >= defender_definition.max_alive {
+				continue;
+			}
+
+			// Launch from the stronghold's centre, heading towards the player
+			let mut defender = spawning::spawn_enemy(
+				defender_definition.enemy_name,
+				Transform::from_translation(stronghold_transform.translation)
+					.with_rotation(Quat::from_rotation_z(std::f32::consts::PI)),
+				&enemy_definition_list,
+				&ship_definition_list,
+				&gun_definition_list,
+				&cartridge_definition_list,
+			);
+			defender.ship.faction = stronghold_definition.faction;
+			commands.spawn_bundle(defender).insert(StrongholdDefender {
+				stronghold: stronghold_entity,
+				defender_index,
+			});
+		}
+	}
+}
+
+/// Keeps a stronghold's sections invulnerable while any of its shield generators stand
+pub fn stronghold_shield_system(
+	mut commands: Commands,
+	strongholds: Query<Entity, With<Stronghold>>,
+	sections: Query<(
+		Entity,
+		&StrongholdSection,
+		Option<&interaction::Invulnerable>,
+	)>,
+) {
+	for stronghold_entity in strongholds.iter() {
+		let shielded = sections.iter().any(|(_, section, _)| {
+			section.stronghold == stronghold_entity && section.shield_generator
+		});
+		for (section_entity, section, invulnerable) in sections.iter() {
+			if section.stronghold != stronghold_entity || section.shield_generator {
+				continue;
+			}
+			if shielded && invulnerable.is_none() {
+				commands
+					.entity(section_entity)
+					.insert(interaction::Invulnerable);
+			} else if !shielded && invulnerable.is_some() {
+				commands
+					.entity(section_entity)
+					.remove::<interaction::Invulnerable>();
+			}
+		}
+	}
+}
+
+/// Removes strongholds with no sections left and ends the stage
+pub fn stronghold_defeat_system(
+	mut commands: Commands,
+	mut stage_complete_events: EventWriter<StageCompleteEvent>,
+	mut level_progress: ResMut<level::LevelProgress>,
+	current_level: Res<level::CurrentLevel>,
+	strongholds: Query<(Entity, &Stronghold)>,
+	sections: Query<&StrongholdSection>,
+) {
+	for (stronghold_entity, stronghold) in strongholds.iter() {
+		if sections
+			.iter()
+			.any(|section| section.stronghold == stronghold_entity)
+		{
+			continue;
+		}
+		commands.entity(stronghold_entity).despawn();
+		level_progress.stage_complete = true;
+		stage_complete_events.send(StageCompleteEvent {
+			level_name: current_level.0,
+			stronghold_name: stronghold.stronghold_name,
+		});
+	}
+}
@@ -0,0 +1,98 @@
+use super::*;
+
+const STRONGHOLD_DATA_PATH: &str = "data/strongholds/";
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Debug)]
+pub enum StrongholdName {
+	TemplateStronghold,
+	Fortress,
+}
+
+/// A destructible part of a stronghold, built from a Ship Definition hull
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StrongholdSectionDefinition {
+	pub ship_name: ship_list::ShipName,
+	/// Overrides the hull's health
+	pub health: ship::Health,
+	/// Position of the section relative to the stronghold's centre
+	pub translation: Vec2,
+	pub rotation_degrees: f32,
+	/// While any shield generator section stands, the stronghold's other sections can't be damaged
+	pub shield_generator: bool,
+	/// Turrets fitted to the hull's turret mounts
+	pub turrets: Vec<loadout_list::TurretLoadoutDefinition>,
+}
+
+/// Enemies the stronghold keeps launching while it stands
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StrongholdDefenderDefinition {
+	pub enemy_name: enemy_list::EnemyName,
+	/// Seconds between launches
+	pub interval_seconds: f32,
+	/// No more are launched while this many are alive
+	pub max_alive: usize,
+}
+
+/// Stronghold definition for storing a stronghold's sections and defenders as YAML
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StrongholdDefinition {
+	pub stronghold_name: StrongholdName,
+	pub faction: faction_list::FactionName,
+	/// Velocity the whole stronghold drifts at, zero for a static structure
+	pub velocity: Vec2,
+	pub sections: Vec<StrongholdSectionDefinition>,
+	pub defenders: Vec<StrongholdDefenderDefinition>,
+}
+
+/// List of Stronghold Definitions
+#[derive(Deref, DerefMut, Debug)]
+pub struct StrongholdDefinitionList(Vec<StrongholdDefinition>);
+
+/// Generates an explanatory template for how a Stronghold Definition should be formatted in YAML,
+/// then writes it out to a template.yaml file.
+pub fn write_stronghold_definition_template() {
+	// Define template
+	let stronghold_definition_template = StrongholdDefinition {
+		stronghold_name: StrongholdName::TemplateStronghold,
+		faction: faction_list::FactionName::Garrison,
+		velocity: Vec2::new(0.0, -5.0),
+		sections: vec![
+			StrongholdSectionDefinition {
+				ship_name: ship_list::ShipName::TemplateShip,
+				health: ship::Health(500.0),
+				translation: Vec2::new(0.0, 0.0),
+				rotation_degrees: 180.0,
+				shield_generator: false,
+				turrets: vec![loadout_list::TurretLoadoutDefinition {
+					mount_number: 0,
+					gun_name: gun_list::GunName::TemplateGun,
+					num_barrels: turret::TurretNumBarrels::Single,
+					ammunition: None,
+					role: turret::TurretRole::General,
+				}],
+			},
+			StrongholdSectionDefinition {
+				ship_name: ship_list::ShipName::TemplateShip,
+				health: ship::Health(200.0),
+				translation: Vec2::new(250.0, 50.0),
+				rotation_degrees: 180.0,
+				shield_generator: true,
+				turrets: Vec::new(),
+			},
+		],
+		defenders: vec![StrongholdDefenderDefinition {
+			enemy_name: enemy_list::EnemyName::TemplateEnemy,
+			interval_seconds: 10.0,
+			max_alive: 3,
+		}],
+	};
+	// Write out template
+	crate::game_io::write_definition_template(STRONGHOLD_DATA_PATH, stronghold_definition_template);
+}
+
+/// Reads all *.yaml Stronghold definition files in data/strongholds/ and returns them as a ```StrongholdDefinitionList```
+pub fn read_stronghold_definitions() -> StrongholdDefinitionList {
+	let stronghold_definition_list =
+		StrongholdDefinitionList(crate::game_io::read_definitions(STRONGHOLD_DATA_PATH));
+	return stronghold_definition_list;
+}
@@ -1,40 +1,243 @@
-use super::{interaction::IFF, *};
+use std::collections::VecDeque;
+
+use super::{faction_list::FactionName, *};
 
 //#[derive(Component)]
 //pub enum MissileTargetingPhase{}
 // Active/semi active/LOBL/LOAL?
 // Relock?
 
+/// Turret target selection and aiming
+pub struct TargetingPlugin;
+
+impl Plugin for TargetingPlugin {
+	fn build(&self, app: &mut App) {
+		app.add_simulation_system_set(
+			SimulationStep::Update,
+			SystemSet::new()
+				.label(SimulationSystem::Targeting)
+				.with_system(acceleration_history_system.before(SimulationSystem::TargetSelection))
+				.with_system(turret_target_selection.label(SimulationSystem::TargetSelection))
+				.with_system(turret_targeting_system.after(SimulationSystem::TargetSelection)),
+		);
+	}
+}
+
+/// Ticks of measured acceleration averaged when leading shots at a ship
+const ACCELERATION_HISTORY_TICKS: usize = 10;
+/// Longest intercept the second order solver looks for, in seconds
+const MAX_INTERCEPT_SECONDS: f32 = 10.0;
+/// Shortest step the intercept search takes, in seconds. Chances to hit shorter than this can be missed.
+const INTERCEPT_SEARCH_MIN_STEP_SECONDS: f32 = 0.005;
+/// Most steps the intercept search takes before giving up on a target that stays just out of reach
+const INTERCEPT_SEARCH_MAX_STEPS: usize = 256;
+const INTERCEPT_BISECTION_ITERATIONS: usize = 30;
+
+/// Where to aim to hit a target, and when the shot meets it
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct InterceptSolution {
+	/// Turret rotation about the Z axis in radians, from pointing along +Y, between -PI and PI.
+	/// In the same frame as the positions the solution was worked out from.
+	pub aim_angle: f32,
+	pub intercept_seconds: f32,
+}
+
+impl InterceptSolution {
+	/// Solution aiming at ```aim_point```, relative to the turret
+	fn from_aim_point(aim_point: Vec2, intercept_seconds: f32) -> Self {
+		Self {
+			aim_angle: aim_heading(aim_point),
+			intercept_seconds,
+		}
+	}
+
+	pub fn rotation(&self) -> Quat {
+		Quat::from_rotation_z(self.aim_angle)
+	}
+}
+
+/// Rotation about the Z axis that turns +Y towards ```direction```, covering the full circle
+pub fn aim_heading(direction: Vec2) -> f32 {
+	// Rotating +Y by the angle gives (-sin, cos)
+	return (-direction.x).atan2(direction.y);
+}
+
+/// Expresses a world ```vector``` in the frame of a parent with ```parent_rotation```,
+/// so a child aimed along the result points along ```vector``` in the world.
+pub fn to_parent_frame(parent_rotation: Quat, vector: Vec2) -> Vec2 {
+	return (parent_rotation.inverse() * vector.extend(0.0)).truncate();
+}
+
+// ==========
+// Components
+
+/// Recent accelerations of a ship, measured from its Velocity every tick, for leading shots at it
+#[derive(Component, Clone, Default, Debug)]
+pub struct AccelerationHistory {
+	last_velocity: Option<Vec2>,
+	samples: VecDeque<Vec2>,
+}
+
+impl AccelerationHistory {
+	/// Measures the acceleration since the last recorded ```velocity```, forgetting the oldest sample once full
+	pub fn record(&mut self, velocity: Vec2) {
+		if let Some(last_velocity) = self.last_velocity {
+			if self.samples.len() == ACCELERATION_HISTORY_TICKS {
+				self.samples.pop_front();
+			}
+			self.samples
+				.push_back((velocity - last_velocity) / SIMULATION_TIMESTEP as f32);
+		}
+		self.last_velocity = Some(velocity);
+	}
+
+	/// Mean acceleration over the history, zero until two velocities have been recorded
+	pub fn estimate(&self) -> Vec2 {
+		if self.samples.is_empty() {
+			return Vec2::ZERO;
+		}
+		return self
+			.samples
+			.iter()
+			.fold(Vec2::ZERO, |sum, &sample| sum + sample)
+			/ self.samples.len() as f32;
+	}
+}
+
+/// Target a ship's turrets engage ahead of any other, while it is hostile and still around
+#[derive(Component, Clone, Copy, Deref, DerefMut, Default, Debug)]
+pub struct PriorityTarget(pub Option<Entity>);
+
+// ==========
+// Systems
+
+/// Records every ship's acceleration for the turrets leading shots at it
+pub fn acceleration_history_system(
+	mut ships: Query<(&physics::Velocity, &mut AccelerationHistory)>,
+) {
+	for (velocity, mut acceleration_history) in ships.iter_mut() {
+		acceleration_history.record(velocity.0);
+	}
+}
+
+/// Picks the closest ship of a hostile faction for each turret, unless its ship has a Priority Target.
+/// Hostile turrets engaging the turret's own ship are fired back at, if they are closer.
+/// Point defence turrets engage the closest hostile Targetable projectile before anything else.
+/// Turrets on destroyed ships stand down, and disabled turrets are left alone.
+/// Invulnerable ships are passed over, there is no point shooting at them.
 pub fn turret_target_selection(
 	mut turrets: Query<(
+		Entity,
 		&mut turret::TurretProperties,
+		&turret::TurretRole,
 		&GlobalTransform,
 		&Parent,
+		&ship::Health,
+	)>,
+	faction_definition_list: Res<faction_list::FactionDefinitionList>,
+	ship_query: Query<(
+		&FactionName,
+		Option<&ship::IsDestroyed>,
+		Option<&PriorityTarget>,
 	)>,
-	ship_query: Query<&IFF>,
-	target_candidates: Query<(Entity, &Transform, &IFF)>,
+	target_candidates: Query<
+		(Entity, &Transform, &FactionName),
+		(
+			With<ship::Health>,
+			Without<ship::IsDestroyed>,
+			Without<projectile::IsProjectile>,
+			Without<interaction::Invulnerable>,
+		),
+	>,
+	projectile_candidates: Query<
+		(Entity, &Transform, &FactionName),
+		(With<projectile::Targetable>, With<ship::Health>),
+	>,
 ) {
-	if target_candidates.is_empty() {
-		for (mut turret, _, _) in turrets.iter_mut() {
-			turret.turret_state = turret::TurretState::NoTarget;
+	if target_candidates.is_empty() && projectile_candidates.is_empty() {
+		for (_, mut turret_properties, _, _, _, _) in turrets.iter_mut() {
+			if turret_properties.turret_state != turret::TurretState::Disabled {
+				turret_properties.turret_state = turret::TurretState::NoTarget;
+			}
 		}
 		return;
 	} else {
-		for (
-			mut turret_properties,
-			turret_global_transform,
-			turret_parent,
-		) in turrets.iter_mut()
+		// Live turrets, and the ships they are engaging, for firing back at
+		let engaging_turrets: Vec<(Entity, Vec3, Entity, Entity)> = turrets
+			.iter()
+			.filter(|(_, turret_properties, _, _, _, health)| {
+				health.0 > 0.0 && turret_properties.turret_state != turret::TurretState::Disabled
+			})
+			.filter_map(
+				|(entity, turret_properties, _, global_transform, parent, _)| {
+					turret_properties
+						.target_entity
+						.map(|target| (entity, global_transform.translation, parent.0, target))
+				},
+			)
+			.collect();
+
+		for (_, mut turret_properties, turret_role, turret_global_transform, turret_parent, _) in
+			turrets.iter_mut()
 		{
+			if turret_properties.turret_state == turret::TurretState::Disabled {
+				continue;
+			}
+			// Find parent ship
+			let (own_faction, own_ship_destroyed, priority_target) = ship_query
+				.get(turret_parent.0)
+				.expect("Failed to get turret's parent ship");
+			if own_ship_destroyed.is_some() {
+				turret_properties.target_entity = None;
+				turret_properties.turret_state = turret::TurretState::NoTarget;
+				continue;
+			}
+
+			// Point defence shoots down incoming projectiles first
+			if *turret_role == turret::TurretRole::PointDefence {
+				let closest_projectile = projectile_candidates
+					.iter()
+					.filter(|(_, _, candidate_faction)| {
+						faction_definition_list.is_hostile(*own_faction, **candidate_faction)
+					})
+					.map(|(candidate_entity, candidate_transform, _)| {
+						let range = (candidate_transform.translation
+							- turret_global_transform.translation)
+							.truncate()
+							.length();
+						(candidate_entity, range)
+					})
+					.min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+				if let Some((projectile_entity, _)) = closest_projectile {
+					turret_properties.target_entity = Some(projectile_entity);
+					continue;
+				}
+			}
+
+			// Engage the ship's Priority Target while it is still a valid hostile
+			let priority_target = priority_target
+				.and_then(|priority_target| priority_target.0)
+				.filter(|&priority_entity| {
+					target_candidates
+						.get(priority_entity)
+						.is_ok_and(|(_, _, candidate_faction)| {
+							faction_definition_list.is_hostile(*own_faction, *candidate_faction)
+						})
+				});
+			if priority_target.is_some() {
+				turret_properties.target_entity = priority_target;
+				continue;
+			}
+
 			let mut target_candidate_entity = None::<Entity>;
 			let mut target_candidate_range = 0.0;
 			//Find best possible target
-			for (candidate_entity, candidate_transform, candidate_iff) in target_candidates.iter() {
-				// Find parent ship
-				let own_iff = ship_query.get(turret_parent.0).expect("msg");
-				// Only proceed if candidate IFF different from own,
+			for (candidate_entity, candidate_transform, candidate_faction) in
+				target_candidates.iter()
+			{
+				// Only proceed if candidate's faction is hostile to our own,
 				//  and Turret's bullets are faster than the target
-				if candidate_iff == own_iff {
+				if !faction_definition_list.is_hostile(*own_faction, *candidate_faction) {
 					continue;
 				}
 				let target_relative_position = (candidate_transform.translation
@@ -50,92 +253,432 @@ pub fn turret_target_selection(
 					target_candidate_range = target_range;
 				}
 			}
+			for &(candidate_entity, candidate_translation, candidate_ship, candidate_target) in
+				engaging_turrets.iter()
+			{
+				if candidate_target != turret_parent.0 {
+					continue;
+				}
+				match ship_query.get(candidate_ship) {
+					Ok((candidate_faction, None, _))
+						if faction_definition_list.is_hostile(*own_faction, *candidate_faction) => {}
+					_ => continue,
+				}
+				let target_range =
+					(candidate_translation - turret_global_transform.translation).length();
+				if target_candidate_entity.is_none() || target_range < target_candidate_range {
+					target_candidate_entity = Some(candidate_entity);
+					target_candidate_range = target_range;
+				}
+			}
 			// Set turret's target to best candidate.
 			turret_properties.target_entity = target_candidate_entity;
+			if target_candidate_entity.is_none() {
+				turret_properties.turret_state = turret::TurretState::NoTarget;
+			}
 		}
 	}
 }
 
+/// Aims turrets at where their target will be when the shot arrives, allowing for its acceleration.
+/// Turrets are rotated relative to their ship, so the intercept is solved in the ship's frame,
+/// taken from the ship's Transform as steering may have turned it this tick.
 pub fn turret_targeting_system(
 	mut turrets: Query<(
 		&Parent,
-		&GlobalTransform,
 		&mut Transform,
 		&mut turret::TurretProperties,
 		&gun::GunProperties,
 	)>,
-	enemies: Query<(Entity, &Transform, &physics::Velocity), Without<turret::TurretProperties>>,
-	ship_query: Query<&physics::Velocity>,
+	enemies: Query<
+		(
+			Entity,
+			&Transform,
+			&physics::Velocity,
+			Option<&AccelerationHistory>,
+		),
+		Without<turret::TurretProperties>,
+	>,
+	enemy_turrets: Query<(&GlobalTransform, &Parent), With<turret::IsTurret>>,
+	ship_query: Query<
+		(&physics::Velocity, &Transform, Option<&AccelerationHistory>),
+		Without<turret::TurretProperties>,
+	>,
 ) {
-	for (
-		turret_parent,
-		turret_global_transform,
-		mut turret_transform,
-		mut turret_properties,
-		gun_properties,
-	) in turrets.iter_mut()
+	for (turret_parent, mut turret_transform, mut turret_properties, gun_properties) in
+		turrets.iter_mut()
 	{
-		let turret_target = turret_properties.target_entity;
-		if turret_target.is_none() {
-			continue; //Turret has no target.
-		} else {
-			let turret_target = enemies.get(turret_target.unwrap());
-			if turret_target.is_err() {
-				continue; // Something Broke.
-			}
-			// Find turret's current target.
-			let (_, target_transform, target_velocity) = turret_target.unwrap();
+		if turret_properties.turret_state == turret::TurretState::Disabled {
+			continue; // Turret is knocked out
+		}
+		if let Some(turret_target) = turret_properties.target_entity {
+			// Find turret's current target. Turrets move with their ship.
+			let (target_translation, target_velocity, target_acceleration_history) =
+				match enemies.get(turret_target) {
+					Ok((_, target_transform, target_velocity, target_acceleration_history)) => (
+						target_transform.translation,
+						target_velocity.0,
+						target_acceleration_history,
+					),
+					Err(_) => match enemy_turrets.get(turret_target) {
+						Ok((target_transform, target_parent)) => {
+							match ship_query.get(target_parent.0) {
+								Ok((target_velocity, _, target_acceleration_history)) => (
+									target_transform.translation,
+									target_velocity.0,
+									target_acceleration_history,
+								),
+								Err(_) => continue,
+							}
+						}
+						Err(_) => continue, // Something Broke.
+					},
+				};
+			// Projectiles don't keep a history, and are led as if they fly straight
+			let target_acceleration = target_acceleration_history
+				.map_or(Vec2::ZERO, |acceleration_history| {
+					acceleration_history.estimate()
+				});
 
-			let relative_position =
-				(target_transform.translation - turret_global_transform.translation).truncate();
+			let (ship_velocity, ship_transform, _) = ship_query.get(turret_parent.0).unwrap();
+			let ship_rotation = ship_transform.rotation;
+			let turret_translation = ship_transform.mul_transform(*turret_transform).translation;
 
-			let ship_velocity = ship_query.get(turret_parent.0).unwrap();
-			let relative_velocity = target_velocity.0 - ship_velocity.0;
+			let relative_position = to_parent_frame(
+				ship_rotation,
+				(target_translation - turret_translation).truncate(),
+			);
+			let relative_velocity =
+				to_parent_frame(ship_rotation, target_velocity - ship_velocity.0);
+			let target_acceleration = to_parent_frame(ship_rotation, target_acceleration);
 
-			let target_point = target_prediction_first_order(
+			let intercept_solution = target_prediction_second_order(
 				relative_position,
 				relative_velocity,
+				target_acceleration,
 				gun_properties.cartridge_data.projectile_velocity_mps,
 			);
-			if target_point.is_some() {
-				turret_transform.rotation = target_point.unwrap();
+			if let Some(intercept_solution) = intercept_solution {
+				turret_transform.rotation = intercept_solution.rotation();
+				turret_properties.intercept_seconds = Some(intercept_solution.intercept_seconds);
 				turret_properties.turret_state = turret::TurretState::Firing;
 			} else {
+				turret_properties.intercept_seconds = None;
 				turret_properties.turret_state = turret::TurretState::NoTarget;
 			}
 		}
 	}
 }
 
+/// Solves for the soonest intercept of a target moving at a constant ```relative_velocity```.
+/// Returns None when the shot can never catch the target.
 pub fn target_prediction_first_order(
 	relative_position: Vec2,
 	relative_velocity: Vec2,
 	projectile_velocity: f32,
-) -> Option<Quat> {
-	let dot = Vec2::dot(relative_position, relative_velocity);
+) -> Option<InterceptSolution> {
 	let target_distance = relative_position.length_squared();
+	if target_distance <= 0.0 {
+		return None; // No direction to aim in
+	}
+	let dot = Vec2::dot(relative_position, relative_velocity);
 	let i_speed2 = projectile_velocity.powi(2);
 	let target_speed = relative_velocity.length_squared();
-	let sqrt = ((dot * dot) - target_distance * (target_speed - i_speed2)).sqrt();
-
-	let whatever_the_hell_this_is = (
-		(-dot - sqrt) / target_distance,
-		(-dot + sqrt) / target_distance,
-	);
-
-	if whatever_the_hell_this_is.0 > 0.0 {
-		return Some(Quat::from_rotation_z(
-			-((whatever_the_hell_this_is.0 * relative_position + relative_velocity).x
-				/ (whatever_the_hell_this_is.0 * relative_position + relative_velocity).y)
-				.atan(),
-		));
-	} else if whatever_the_hell_this_is.1 > 0.0 {
-		return Some(Quat::from_rotation_z(
-			-((whatever_the_hell_this_is.1 * relative_position + relative_velocity).x
-				/ (whatever_the_hell_this_is.1 * relative_position + relative_velocity).y)
-				.atan(),
-		));
+	let discriminant = (dot * dot) - target_distance * (target_speed - i_speed2);
+	if discriminant < 0.0 {
+		return None; // Target outruns the shot
+	}
+	let sqrt = discriminant.sqrt();
+
+	// Roots are the reciprocal of the intercept time, so the larger positive root is the sooner intercept
+	let root = if (-dot + sqrt) / target_distance > 0.0 {
+		(-dot + sqrt) / target_distance
+	} else if (-dot - sqrt) / target_distance > 0.0 {
+		(-dot - sqrt) / target_distance
 	} else {
 		return None;
+	};
+	let intercept_seconds = root.recip();
+	return Some(InterceptSolution::from_aim_point(
+		relative_position + relative_velocity * intercept_seconds,
+		intercept_seconds,
+	));
+}
+
+/// Solves for the soonest intercept of a target that also keeps up a constant ```target_acceleration```.
+/// There's no closed form worth having, so the first time the shot can reach the target is searched for,
+/// up to ```MAX_INTERCEPT_SECONDS``` ahead. Returns None when the shot can't catch the target in that time,
+/// or the search runs out of steps.
+pub fn target_prediction_second_order(
+	relative_position: Vec2,
+	relative_velocity: Vec2,
+	target_acceleration: Vec2,
+	projectile_velocity: f32,
+) -> Option<InterceptSolution> {
+	if target_acceleration == Vec2::ZERO {
+		return target_prediction_first_order(
+			relative_position,
+			relative_velocity,
+			projectile_velocity,
+		)
+		.filter(|solution| solution.intercept_seconds <= MAX_INTERCEPT_SECONDS);
+	}
+	if relative_position.length_squared() <= 0.0 {
+		return None; // No direction to aim in
+	}
+
+	let target_offset = |seconds: f32| {
+		relative_position
+			+ relative_velocity * seconds
+			+ 0.5 * target_acceleration * seconds * seconds
+	};
+	// How far the target is beyond the shot's reach after ```seconds```, negative once it can be hit
+	let shortfall = |seconds: f32| target_offset(seconds).length() - projectile_velocity * seconds;
+
+	// The shortfall can't close faster than this, so stepping ahead by the shortfall over it
+	// can't step past a chance to hit, down to the minimum step
+	let max_closing_speed = relative_velocity.length()
+		+ target_acceleration.length() * MAX_INTERCEPT_SECONDS
+		+ projectile_velocity;
+	let mut early = 0.0;
+	for _ in 0..INTERCEPT_SEARCH_MAX_STEPS {
+		if early >= MAX_INTERCEPT_SECONDS {
+			return None;
+		}
+		let step_seconds =
+			(shortfall(early) / max_closing_speed).max(INTERCEPT_SEARCH_MIN_STEP_SECONDS);
+		let mut late = (early + step_seconds).min(MAX_INTERCEPT_SECONDS);
+		if shortfall(late) > 0.0 {
+			early = late;
+			continue;
+		}
+		// Narrow down on when the shot first reaches the target
+		for _ in 0..INTERCEPT_BISECTION_ITERATIONS {
+			let middle = 0.5 * (early + late);
+			if shortfall(middle) > 0.0 {
+				early = middle;
+			} else {
+				late = middle;
+			}
+		}
+		return Some(InterceptSolution::from_aim_point(target_offset(late), late));
+	}
+	return None;
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const TOLERANCE: f32 = 1e-3;
+
+	fn assert_close(actual: f32, expected: f32) {
+		assert!(
+			(actual - expected).abs() < TOLERANCE,
+			"expected {}, got {}",
+			expected,
+			actual
+		);
+	}
+
+	#[test]
+	fn first_order_stationary_target() {
+		let solution =
+			target_prediction_first_order(Vec2::new(0.0, 100.0), Vec2::ZERO, 50.0).unwrap();
+		assert_close(solution.intercept_seconds, 2.0);
+		assert_close(solution.aim_angle, 0.0);
+	}
+
+	#[test]
+	fn first_order_crossing_target() {
+		// |(30t, 100)| = 50t gives t = 2.5, meeting the target at (75, 100)
+		let solution =
+			target_prediction_first_order(Vec2::new(0.0, 100.0), Vec2::new(30.0, 0.0), 50.0)
+				.unwrap();
+		assert_close(solution.intercept_seconds, 2.5);
+		assert_close(solution.aim_angle, -(0.75_f32).atan());
+	}
+
+	#[test]
+	fn first_order_target_outrunning_shot() {
+		let solution =
+			target_prediction_first_order(Vec2::new(0.0, 100.0), Vec2::new(0.0, 60.0), 50.0);
+		assert_eq!(solution, None);
+	}
+
+	#[test]
+	fn target_on_turret_has_no_solution() {
+		assert_eq!(
+			target_prediction_first_order(Vec2::ZERO, Vec2::new(10.0, 0.0), 50.0),
+			None
+		);
+		assert_eq!(
+			target_prediction_second_order(Vec2::ZERO, Vec2::ZERO, Vec2::new(10.0, 0.0), 50.0),
+			None
+		);
+	}
+
+	#[test]
+	fn second_order_without_acceleration_matches_first_order() {
+		let first_order =
+			target_prediction_first_order(Vec2::new(40.0, 100.0), Vec2::new(-20.0, 5.0), 50.0);
+		let second_order = target_prediction_second_order(
+			Vec2::new(40.0, 100.0),
+			Vec2::new(-20.0, 5.0),
+			Vec2::ZERO,
+			50.0,
+		);
+		assert_eq!(first_order, second_order);
+	}
+
+	#[test]
+	fn second_order_target_accelerating_away() {
+		// 100 + 5t² = 50t gives t = 5 - √5
+		let solution = target_prediction_second_order(
+			Vec2::new(0.0, 100.0),
+			Vec2::ZERO,
+			Vec2::new(0.0, 10.0),
+			50.0,
+		)
+		.unwrap();
+		assert_close(solution.intercept_seconds, 5.0 - 5.0_f32.sqrt());
+		assert_close(solution.aim_angle, 0.0);
+	}
+
+	#[test]
+	fn second_order_target_accelerating_across() {
+		// |(10t², 100)| = 50t gives t² = 5, meeting the target at (50, 100)
+		let solution = target_prediction_second_order(
+			Vec2::new(0.0, 100.0),
+			Vec2::ZERO,
+			Vec2::new(20.0, 0.0),
+			50.0,
+		)
+		.unwrap();
+		assert_close(solution.intercept_seconds, 5.0_f32.sqrt());
+		assert_close(solution.aim_angle, -(0.5_f32).atan());
+	}
+
+	#[test]
+	fn second_order_target_outrunning_shot() {
+		// 100 + 10t² = 10t has no real roots
+		let solution = target_prediction_second_order(
+			Vec2::new(0.0, 100.0),
+			Vec2::ZERO,
+			Vec2::new(0.0, 20.0),
+			10.0,
+		);
+		assert_eq!(solution, None);
+	}
+
+	#[test]
+	fn second_order_target_in_reach_briefly() {
+		// 100 - 54t + 27.0375t² = 50t only from t = 103 / 54.075 to t = 105 / 54.075
+		let solution = target_prediction_second_order(
+			Vec2::new(0.0, 100.0),
+			Vec2::new(0.0, -54.0),
+			Vec2::new(0.0, 54.075),
+			50.0,
+		)
+		.unwrap();
+		assert_close(solution.intercept_seconds, 103.0 / 54.075);
+	}
+
+	#[test]
+	fn second_order_search_gives_up_on_target_just_out_of_reach() {
+		// Pulls away at the shot's speed a meter ahead, and slowly drifts sideways
+		assert_eq!(
+			target_prediction_second_order(
+				Vec2::new(0.0, 1.0),
+				Vec2::new(0.0, 50.0),
+				Vec2::new(0.01, 0.0),
+				50.0
+			),
+			None
+		);
+	}
+
+	#[test]
+	fn intercepts_beyond_the_horizon_are_dropped() {
+		// A stationary target 1000 meters away takes 20 seconds to reach
+		assert!(target_prediction_first_order(Vec2::new(0.0, 1000.0), Vec2::ZERO, 50.0).is_some());
+		assert_eq!(
+			target_prediction_second_order(Vec2::new(0.0, 1000.0), Vec2::ZERO, Vec2::ZERO, 50.0),
+			None
+		);
+		assert_eq!(
+			target_prediction_second_order(
+				Vec2::new(0.0, 1000.0),
+				Vec2::ZERO,
+				Vec2::new(0.0, 0.1),
+				50.0
+			),
+			None
+		);
+	}
+
+	#[test]
+	fn acceleration_history_averages_measured_acceleration() {
+		let mut acceleration_history = AccelerationHistory::default();
+		assert_eq!(acceleration_history.estimate(), Vec2::ZERO);
+		let timestep = SIMULATION_TIMESTEP as f32;
+		for tick in 0..=ACCELERATION_HISTORY_TICKS * 2 {
+			acceleration_history.record(Vec2::new(0.0, 6.0 * timestep * tick as f32));
+		}
+		assert_close(acceleration_history.estimate().x, 0.0);
+		assert_close(acceleration_history.estimate().y, 6.0);
+	}
+
+	/// Checks the solution for a stationary target at ```target``` points a turret straight at it
+	fn assert_aims_at(target: Vec2, expected_angle: f32) {
+		let solution = target_prediction_first_order(target, Vec2::ZERO, 50.0).unwrap();
+		assert_close(solution.aim_angle, expected_angle);
+		let aim_direction = (solution.rotation() * Vec3::Y).truncate();
+		assert_close(aim_direction.x, target.normalize().x);
+		assert_close(aim_direction.y, target.normalize().y);
+	}
+
+	#[test]
+	fn aims_into_first_quadrant() {
+		assert_aims_at(Vec2::new(100.0, 100.0), -std::f32::consts::FRAC_PI_4);
+	}
+
+	#[test]
+	fn aims_into_second_quadrant() {
+		assert_aims_at(Vec2::new(-100.0, 100.0), std::f32::consts::FRAC_PI_4);
+	}
+
+	#[test]
+	fn aims_into_third_quadrant() {
+		assert_aims_at(Vec2::new(-100.0, -100.0), 3.0 * std::f32::consts::FRAC_PI_4);
+	}
+
+	#[test]
+	fn aims_into_fourth_quadrant() {
+		assert_aims_at(Vec2::new(100.0, -100.0), -3.0 * std::f32::consts::FRAC_PI_4);
+	}
+
+	#[test]
+	fn aims_along_x_axis() {
+		assert_aims_at(Vec2::new(100.0, 0.0), -std::f32::consts::FRAC_PI_2);
+		assert_aims_at(Vec2::new(-100.0, 0.0), std::f32::consts::FRAC_PI_2);
+	}
+
+	#[test]
+	fn aims_relative_to_rotated_ship() {
+		// Ship turned to face -X, with the target off to +X in the world
+		let ship_rotation = Quat::from_rotation_z(std::f32::consts::FRAC_PI_2);
+		let world_target = Vec2::new(100.0, 0.0);
+		let solution = target_prediction_first_order(
+			to_parent_frame(ship_rotation, world_target),
+			Vec2::ZERO,
+			50.0,
+		)
+		.unwrap();
+		// Turret has to face straight back over the stern
+		assert_close(solution.aim_angle.abs(), std::f32::consts::PI);
+		let world_aim_direction = (ship_rotation * solution.rotation() * Vec3::Y).truncate();
+		assert_close(world_aim_direction.x, 1.0);
+		assert_close(world_aim_direction.y, 0.0);
 	}
 }
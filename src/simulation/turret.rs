@@ -3,8 +3,24 @@ use super::*;
 const BASE_TEXTURES_PATH_TURRETS: &str = "textures/turrets/";
 const BASE_TEXTURES_PATH_GUNS: &str = "textures/guns/";
 
+/// Knocking out and repairing turrets
+pub struct TurretPlugin;
+
+impl Plugin for TurretPlugin {
+	fn build(&self, app: &mut App) {
+		app.add_simulation_system_set(
+			SimulationStep::Update,
+			SystemSet::new()
+				.after(SimulationSystem::Damage)
+				.before(SimulationSystem::Cleanup)
+				.with_system(turret_destroyed_system)
+				.with_system(turret_repair_system.after(turret_destroyed_system)),
+		);
+	}
+}
+
 /// The number of gun barrels on the turret
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Serialize, Deserialize, Debug)]
 pub enum TurretNumBarrels {
 	Single,
 	Double,
@@ -21,6 +37,23 @@ pub enum TurretState {
 	NoTarget,
 	//	Laying,
 	Firing,
+	/// Knocked out, and won't select targets or fire
+	Disabled,
+}
+
+/// What a turret engages first
+#[derive(Component, Clone, Copy, PartialEq, Serialize, Deserialize, Debug)]
+pub enum TurretRole {
+	/// Engages the closest hostile ship
+	General,
+	/// Engages the closest incoming Targetable projectile, like a missile, before any ship
+	PointDefence,
+}
+
+impl Default for TurretRole {
+	fn default() -> Self {
+		TurretRole::General
+	}
 }
 
 /// Holds the turret mount number the turret is assigned to
@@ -33,6 +66,8 @@ pub struct TurretProperties {
 	pub rotation_velocity: Quat,
 	pub target_entity: Option<Entity>,
 	pub turret_state: TurretState,
+	/// Seconds until a shot fired now meets the target, as last predicted
+	pub intercept_seconds: Option<f32>,
 }
 
 impl Default for TurretProperties {
@@ -41,6 +76,7 @@ impl Default for TurretProperties {
 			rotation_velocity: Quat::from_rotation_z((10.0_f32).to_radians()),
 			target_entity: None,
 			turret_state: TurretState::NoTarget,
+			intercept_seconds: None,
 		}
 	}
 }
@@ -48,7 +84,7 @@ impl Default for TurretProperties {
 #[derive(Clone, Debug)]
 pub struct TurretGunAssignment {
 	pub transform: Transform,
-	pub texture: Handle<Image>,
+	pub texture_path: String,
 	/// Defines the size the texture should be rendered at in meters.
 	pub texture_size: Vec2,
 }
@@ -62,8 +98,13 @@ pub struct TurretBundle {
 	pub is_turret: IsTurret,
 	pub turret_size: ItemSize,
 	pub turret_mount_number: TurretMountNumber,
+	pub turret_role: TurretRole,
 	pub turret_properties: TurretProperties,
 
+	pub health: ship::Health,
+	pub max_health: ship::MaxHealth,
+	pub collider: physics::Collider,
+
 	pub transform: Transform,
 	pub global_transform: GlobalTransform,
 
@@ -73,9 +114,7 @@ pub struct TurretBundle {
 	/// List of child gun components
 	pub gun_assignment_list: TurretGunAssignmentList,
 
-	pub sprite: Sprite,
-	pub texture: Handle<Image>,
-	pub visibility: Visibility,
+	pub appearance: Appearance,
 }
 
 impl Default for TurretBundle {
@@ -87,75 +126,85 @@ impl Default for TurretBundle {
 			gun_properties: gun::GunProperties::default(),
 			gun_assignment_list: TurretGunAssignmentList(None),
 			turret_mount_number: TurretMountNumber(0),
+			turret_role: default(),
+			health: ship::Health(1.0),
+			max_health: ship::MaxHealth(1.0),
+			collider: default(),
 			transform: default(),
 			global_transform: default(),
-			sprite: default(),
-			texture: default(),
-			visibility: default(),
+			appearance: default(),
 		}
 	}
 }
 
-/// Returns a turrets texture, and texture_size based on gun_name, size, and num_barrels
+/// Health of a turret of ```turret_size```
+pub fn turret_max_health(turret_size: ItemSize) -> f32 {
+	match turret_size {
+		ItemSize::Small => 15.0,
+		ItemSize::Medium => 40.0,
+		ItemSize::Large => 80.0,
+	}
+}
+
+/// Returns a turrets texture path, and texture_size based on gun_name, size, and num_barrels
+#[allow(clippy::match_single_binding)] // Room for exceptions for specific guns
 pub fn lookup_turret_texture(
-	asset_server: &Res<AssetServer>,
 	gun_name: gun_list::GunName,
 	turret_mount_size: ItemSize,
 	number_barrels: TurretNumBarrels,
-) -> (Handle<Image>, Vec2) {
-	let (turret_texture, turret_texture_size) = match gun_name {
+) -> (String, Vec2) {
+	let (turret_texture_path, turret_texture_size) = match gun_name {
 		//Exceptions for specific guns
 		_ => match turret_mount_size {
 			ItemSize::Small => match number_barrels {
 				TurretNumBarrels::Single => (
-					asset_server.load(&format!("{}temp_turret.png", BASE_TEXTURES_PATH_TURRETS)),
+					format!("{}temp_turret.png", BASE_TEXTURES_PATH_TURRETS),
 					Vec2::new(20.0, 20.0),
 				),
 				TurretNumBarrels::Double => (
-					asset_server.load(&format!("{}temp_turret.png", BASE_TEXTURES_PATH_TURRETS)),
+					format!("{}temp_turret.png", BASE_TEXTURES_PATH_TURRETS),
 					Vec2::new(20.0, 20.0),
 				),
 				TurretNumBarrels::Triple => (
-					asset_server.load(&format!("{}temp_turret.png", BASE_TEXTURES_PATH_TURRETS)),
+					format!("{}temp_turret.png", BASE_TEXTURES_PATH_TURRETS),
 					Vec2::new(20.0, 20.0),
 				),
 			},
 			ItemSize::Medium => match number_barrels {
 				TurretNumBarrels::Single => (
-					asset_server.load(&format!("{}temp_turret.png", BASE_TEXTURES_PATH_TURRETS)),
+					format!("{}temp_turret.png", BASE_TEXTURES_PATH_TURRETS),
 					Vec2::new(40.0, 40.0),
 				),
 				TurretNumBarrels::Double => (
-					asset_server.load(&format!("{}temp_turret.png", BASE_TEXTURES_PATH_TURRETS)),
+					format!("{}temp_turret.png", BASE_TEXTURES_PATH_TURRETS),
 					Vec2::new(40.0, 40.0),
 				),
 				TurretNumBarrels::Triple => (
-					asset_server.load(&format!("{}temp_turret.png", BASE_TEXTURES_PATH_TURRETS)),
+					format!("{}temp_turret.png", BASE_TEXTURES_PATH_TURRETS),
 					Vec2::new(40.0, 40.0),
 				),
 			},
 			ItemSize::Large => match number_barrels {
 				TurretNumBarrels::Single => (
-					asset_server.load(&format!("{}temp_turret.png", BASE_TEXTURES_PATH_TURRETS)),
+					format!("{}temp_turret.png", BASE_TEXTURES_PATH_TURRETS),
 					Vec2::new(80.0, 80.0),
 				),
 				TurretNumBarrels::Double => (
-					asset_server.load(&format!("{}temp_turret.png", BASE_TEXTURES_PATH_TURRETS)),
+					format!("{}temp_turret.png", BASE_TEXTURES_PATH_TURRETS),
 					Vec2::new(80.0, 80.0),
 				),
 				TurretNumBarrels::Triple => (
-					asset_server.load(&format!("{}temp_turret.png", BASE_TEXTURES_PATH_TURRETS)),
+					format!("{}temp_turret.png", BASE_TEXTURES_PATH_TURRETS),
 					Vec2::new(80.0, 80.0),
 				),
 			},
 		},
 	};
-	return (turret_texture, turret_texture_size);
+	return (turret_texture_path, turret_texture_size);
 }
 
 /// Generates a Gun List (placing guns relative to the parent turret)
 pub fn generate_turret_gun_list(
-	asset_server: &Res<AssetServer>,
 	turret_size: ItemSize,
 	turret_num_barrels: turret::TurretNumBarrels,
 	gun_texture_path: &str,
@@ -212,7 +261,7 @@ pub fn generate_turret_gun_list(
 	];
 
 	// Set gun texture
-	let gun_texture = asset_server.load(&(BASE_TEXTURES_PATH_GUNS.to_string() + gun_texture_path));
+	let gun_texture_path = BASE_TEXTURES_PATH_GUNS.to_string() + gun_texture_path;
 
 	let mut gun_list = Vec::new();
 	let gun_transforms = match (turret_size, turret_num_barrels) {
@@ -228,10 +277,10 @@ pub fn generate_turret_gun_list(
 	};
 
 	for &gun_transform in gun_transforms.iter() {
-		if gun_transform.is_some() {
+		if let Some(gun_transform) = gun_transform {
 			gun_list.push(TurretGunAssignment {
-				transform: Transform::from_translation(Vec3::from(gun_transform.unwrap())),
-				texture: gun_texture.clone(),
+				transform: Transform::from_translation(Vec3::from(gun_transform)),
+				texture_path: gun_texture_path.clone(),
 				texture_size: Vec2::new(10.0, 10.0),
 			});
 		}
@@ -240,6 +289,16 @@ pub fn generate_turret_gun_list(
 	return TurretGunAssignmentList(Some(gun_list));
 }
 
+// ==========
+// Events
+
+/// Sent when a turret's Health runs out and it goes offline
+#[derive(Debug)]
+pub struct TurretDestroyedEvent {
+	pub turret: Entity,
+	pub ship: Entity,
+}
+
 // ==========
 // Systems
 
@@ -301,3 +360,56 @@ pub fn ship_turret_spawn_system(
 		}
 	}
 }
+
+/// Takes turrets offline once their Health runs out. They stay on their mount until repaired.
+pub fn turret_destroyed_system(
+	mut turret_destroyed_events: EventWriter<TurretDestroyedEvent>,
+	mut turrets: Query<
+		(Entity, &ship::Health, &mut TurretProperties, &Parent),
+		(With<IsTurret>, Changed<ship::Health>),
+	>,
+) {
+	for (turret_entity, health, mut turret_properties, parent) in turrets.iter_mut() {
+		if health.0 <= 0.0 {
+			turret_properties.target_entity = None;
+			turret_properties.turret_state = TurretState::Disabled;
+			turret_destroyed_events.send(TurretDestroyedEvent {
+				turret: turret_entity,
+				ship: parent.0,
+			});
+		}
+	}
+}
+
+/// Repairs turrets on ships allied with the player once the stage is complete, ready for the next battle.
+/// Turrets on destroyed Hull Sections are disabled again by the Hull systems.
+pub fn turret_repair_system(
+	mut stage_complete_events: EventReader<stronghold::StageCompleteEvent>,
+	mut turrets: Query<
+		(
+			&mut ship::Health,
+			&ship::MaxHealth,
+			&mut TurretProperties,
+			&Parent,
+		),
+		With<IsTurret>,
+	>,
+	faction_definition_list: Res<faction_list::FactionDefinitionList>,
+	ships: Query<&faction_list::FactionName>,
+) {
+	if stage_complete_events.iter().count() == 0 {
+		return;
+	}
+	for (mut health, max_health, mut turret_properties, parent) in turrets.iter_mut() {
+		let allied = ships.get(parent.0).is_ok_and(|&faction| {
+			faction_definition_list.is_allied(faction_list::PLAYER_FACTION, faction)
+		});
+		if !allied {
+			continue;
+		}
+		health.0 = max_health.0;
+		if turret_properties.turret_state == TurretState::Disabled {
+			turret_properties.turret_state = TurretState::NoTarget;
+		}
+	}
+}
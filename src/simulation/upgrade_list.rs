@@ -0,0 +1,82 @@
+use super::*;
+
+const UPGRADE_DATA_PATH: &str = "data/upgrades/";
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Debug)]
+pub enum UpgradeName {
+	TemplateUpgrade,
+	LightShield,
+	HeavyShield,
+}
+
+/// Kinds of upgrade, a ship can be fitted with one upgrade of each
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, Debug)]
+pub enum UpgradeCategory {
+	Shield,
+}
+
+/// What fitting an upgrade does to the ship
+#[derive(Clone, Copy, Serialize, Deserialize, Debug)]
+pub enum UpgradeEffect {
+	/// Replaces the hull's shield
+	Shield(shield::ShieldDefinition),
+}
+
+impl UpgradeEffect {
+	pub fn category(&self) -> UpgradeCategory {
+		match self {
+			UpgradeEffect::Shield(_) => UpgradeCategory::Shield,
+		}
+	}
+
+	/// Fits the upgrade to ```ship_bundle```
+	pub fn apply(&self, ship_bundle: &mut ship::ShipBundle) {
+		match self {
+			UpgradeEffect::Shield(shield_definition) => {
+				ship_bundle.shield = shield::Shield::new(*shield_definition);
+			}
+		}
+	}
+}
+
+/// Upgrade definition for storing a purchasable ship upgrade as YAML
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UpgradeDefinition {
+	pub upgrade_name: UpgradeName,
+	/// Price to buy the upgrade
+	pub cost: u32,
+	pub effect: UpgradeEffect,
+}
+
+/// List of Upgrade Definitions
+#[derive(Deref, DerefMut, Debug)]
+pub struct UpgradeDefinitionList(Vec<UpgradeDefinition>);
+
+/// Generates an explanatory template for how an Upgrade Definition should be formatted in YAML,
+/// then writes it out to a template.yaml file.
+pub fn write_upgrade_definition_template() {
+	// Define template
+	let upgrade_definition_template = UpgradeDefinition {
+		upgrade_name: UpgradeName::TemplateUpgrade,
+		cost: 100,
+		effect: UpgradeEffect::Shield(shield::ShieldDefinition {
+			capacity: 50.0,
+			recharge_rate: 5.0,
+			recharge_delay_seconds: 3.0,
+			damage_multipliers: shield::ShieldDamageMultipliers {
+				kinetic: 1.0,
+				explosive: 0.75,
+				energy: 1.5,
+			},
+		}),
+	};
+	// Write out template
+	crate::game_io::write_definition_template(UPGRADE_DATA_PATH, upgrade_definition_template);
+}
+
+/// Reads all *.yaml Upgrade definition files in data/upgrades/ and returns them as an ```UpgradeDefinitionList```
+pub fn read_upgrade_definitions() -> UpgradeDefinitionList {
+	let upgrade_definition_list =
+		UpgradeDefinitionList(crate::game_io::read_definitions(UPGRADE_DATA_PATH));
+	return upgrade_definition_list;
+}
@@ -0,0 +1,426 @@
+use super::{wave_list::*, *};
+use rand::Rng;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Distance from the player ship to the top and bottom edges of the play area
+const SPAWN_EDGE_DISTANCE_Y: f32 = 900.0;
+/// Distance from the player ship to the left and right edges of the play area
+const SPAWN_EDGE_DISTANCE_X: f32 = 1100.0;
+/// How far along an edge enemies can spawn, either side of the player ship
+const SPAWN_EDGE_HALF_LENGTH: f32 = 800.0;
+/// How far past the spawn edges a wave member can fly before it has left the play area
+const PLAY_AREA_MARGIN: f32 = 400.0;
+
+/// Marks an enemy as spawned by the numbered wave
+#[derive(Component, Clone, Copy, Deref, DerefMut, Debug)]
+pub struct WaveMember(pub usize);
+
+/// An enemy from a started wave that is waiting for its spawn delay
+#[derive(Clone, Debug)]
+pub struct PendingWaveSpawn {
+	pub wave_number: usize,
+	pub enemy_name: enemy_list::EnemyName,
+	/// Spawn point relative to the player ship
+	pub offset_from_player: Vec2,
+	/// Direction the enemy sets off in
+	pub heading: Vec2,
+	pub seconds_remaining: f32,
+	/// The enemy's place in a formation it keeps after spawning
+	pub formation_slot: Option<PendingFormationSlot>,
+}
+
+/// Where a pending enemy sits in a kept formation
+#[derive(Clone, Copy, Debug)]
+pub struct PendingFormationSlot {
+	/// Which of the director's formations the enemy belongs to
+	pub formation_id: usize,
+	/// Slot relative to the leader, which sits at zero
+	pub offset_from_leader: Vec2,
+}
+
+/// Drives a Wave Set: starts waves when their triggers are met and spawns their enemies
+#[derive(Debug)]
+pub struct WaveDirector {
+	pub wave_set_name: WaveSetName,
+	/// Index of the next wave to start within the wave set
+	pub next_wave_index: usize,
+	/// Number of waves started so far, counting repeats. Also the number of the current wave.
+	pub waves_started: usize,
+	/// Numbers of the started waves that haven't been cleared yet
+	pub uncleared_waves: BTreeSet<usize>,
+	pub seconds_since_wave_start: f32,
+	/// Seconds since the battle started
+	pub elapsed_seconds: f32,
+	/// Meters the player ship has travelled along the level's track, from ```level::LevelProgress```
+	pub player_distance_travelled: f32,
+	pub pending_spawns: Vec<PendingWaveSpawn>,
+	/// Id to give the next kept formation
+	pub next_formation_id: usize,
+	/// Leaders of kept formations that still have members waiting to spawn
+	pub formation_leaders: BTreeMap<usize, Entity>,
+}
+
+impl WaveDirector {
+	pub fn new(wave_set_name: WaveSetName) -> Self {
+		Self {
+			wave_set_name,
+			next_wave_index: 0,
+			waves_started: 0,
+			uncleared_waves: BTreeSet::new(),
+			seconds_since_wave_start: 0.0,
+			elapsed_seconds: 0.0,
+			player_distance_travelled: 0.0,
+			pending_spawns: Vec::new(),
+			next_formation_id: 0,
+			formation_leaders: BTreeMap::new(),
+		}
+	}
+
+	/// Whether ```trigger``` has been met for the next wave
+	pub fn is_triggered(&self, trigger: WaveTrigger) -> bool {
+		match trigger {
+			WaveTrigger::Time(seconds) => self.seconds_since_wave_start >= seconds,
+			WaveTrigger::PreviousWaveCleared => !self.uncleared_waves.contains(&self.waves_started),
+			WaveTrigger::PlayerProgress(distance) => self.player_distance_travelled >= distance,
+		}
+	}
+}
+
+impl Default for WaveDirector {
+	fn default() -> Self {
+		WaveDirector::new(WaveSetName::default())
+	}
+}
+
+// ==========
+// Events
+
+/// Sent when a wave starts
+#[derive(Debug)]
+pub struct WaveStartedEvent {
+	pub wave_number: usize,
+}
+
+/// Sent when every enemy of a wave has been spawned and destroyed, or has left the play area
+#[derive(Debug)]
+pub struct WaveEndedEvent {
+	pub wave_number: usize,
+}
+
+/// Returns a point on ```edge``` relative to the player ship, the direction into the play area,
+/// and the direction along the edge. ```edge_position``` runs from -1 to 1 along the edge.
+pub fn edge_spawn_point(edge: SpawnEdge, edge_position: f32) -> (Vec2, Vec2, Vec2) {
+	let edge_position = edge_position * SPAWN_EDGE_HALF_LENGTH;
+	match edge {
+		SpawnEdge::Top => (
+			Vec2::new(edge_position, SPAWN_EDGE_DISTANCE_Y),
+			Vec2::new(0.0, -1.0),
+			Vec2::new(1.0, 0.0),
+		),
+		SpawnEdge::Bottom => (
+			Vec2::new(edge_position, -SPAWN_EDGE_DISTANCE_Y),
+			Vec2::new(0.0, 1.0),
+			Vec2::new(1.0, 0.0),
+		),
+		SpawnEdge::Left => (
+			Vec2::new(-SPAWN_EDGE_DISTANCE_X, edge_position),
+			Vec2::new(1.0, 0.0),
+			Vec2::new(0.0, 1.0),
+		),
+		SpawnEdge::Right => (
+			Vec2::new(SPAWN_EDGE_DISTANCE_X, edge_position),
+			Vec2::new(-1.0, 0.0),
+			Vec2::new(0.0, 1.0),
+		),
+	}
+}
+
+/// Returns where each of ```count``` enemies of a group spawns relative to the player ship,
+/// the direction they head in, and for formations that are kept, their slot relative to the leader
+pub fn wave_group_spawn_points(
+	wave_group: &WaveGroupDefinition,
+	count: usize,
+	formation_definition_list: &formation_list::FormationDefinitionList,
+	rng: &mut SimulationRng,
+) -> Vec<(Vec2, Vec2, Option<Vec2>)> {
+	// Group spawn point, and the direction into the play area
+	let (anchor, heading) = match wave_group.spawn_position {
+		SpawnPosition::Edge(edge) => {
+			let (anchor, heading, _) = edge_spawn_point(edge, rng.gen::<f32>() * 2.0 - 1.0);
+			(anchor, heading)
+		}
+		SpawnPosition::Point(point) => (point, (-point).normalize_or_zero()),
+	};
+
+	let mut spawn_points = Vec::new();
+	match wave_group.formation {
+		WaveFormation::Scattered => {
+			for _ in 0..count {
+				let spawn_point = match wave_group.spawn_position {
+					SpawnPosition::Edge(edge) => {
+						edge_spawn_point(edge, rng.gen::<f32>() * 2.0 - 1.0).0
+					}
+					SpawnPosition::Point(_) => {
+						anchor
+							+ Vec2::new(
+								rng.gen::<f32>() * 100.0 - 50.0,
+								rng.gen::<f32>() * 100.0 - 50.0,
+							)
+					}
+				};
+				spawn_points.push((spawn_point, heading, None));
+			}
+		}
+		WaveFormation::Formation(formation_name) => {
+			let formation_definition = match formation_definition_list
+				.iter()
+				.find(|&formation_definition| formation_definition.formation_name == formation_name)
+			{
+				Some(val) => val,
+				None => panic!(
+					"Failed to find {:?} in Formation definitions list!",
+					formation_name
+				),
+			};
+			let slot_offsets = formation_definition.shape.slot_offsets(count);
+
+			if let formation_list::FormationShape::Ring { .. } = formation_definition.shape {
+				// Rings close in on the player from all sides, wherever the group was set to spawn
+				for slot_offset in slot_offsets {
+					spawn_points.push((slot_offset, (-slot_offset).normalize_or_zero(), None));
+				}
+			} else {
+				// Turn slots from the formation's frame into the world's
+				let right = -heading.perp();
+				for slot_offset in slot_offsets {
+					let offset_from_leader = right * slot_offset.x + heading * slot_offset.y;
+					spawn_points.push((
+						anchor + offset_from_leader,
+						heading,
+						if formation_definition.keep_formation {
+							Some(offset_from_leader)
+						} else {
+							None
+						},
+					));
+				}
+			}
+		}
+	}
+	return spawn_points;
+}
+
+// ==========
+// Systems
+
+/// Starts waves when their triggers are met, spawns their enemies when due, and reports cleared waves.
+/// Enemy counts, health, speed and types are scaled by the Difficulty Curve.
+pub fn wave_director_system(
+	mut commands: Commands,
+	mut rng: ResMut<SimulationRng>,
+	mut wave_director: ResMut<WaveDirector>,
+	mut wave_started_events: EventWriter<WaveStartedEvent>,
+	mut wave_ended_events: EventWriter<WaveEndedEvent>,
+	difficulty_curve: Res<spawning::DifficultyCurve>,
+	difficulty_curve_definition_list: Res<difficulty_list::DifficultyCurveDefinitionList>,
+	wave_set_definition_list: Res<WaveSetDefinitionList>,
+	enemy_definition_list: Res<enemy_list::EnemyDefinitionList>,
+	formation_definition_list: Res<formation_list::FormationDefinitionList>,
+	ship_definition_list: Res<ship_list::ShipDefinitionList>,
+	gun_definition_list: Res<gun_list::GunDefinitionList>,
+	cartridge_definition_list: Res<cartridge_list::CartridgeDefinitionList>,
+	level_progress: Res<level::LevelProgress>,
+	player_ship: Query<&Transform, With<ship::IsPlayerShip>>,
+	wave_members: Query<&WaveMember>,
+) {
+	let timestep = SIMULATION_TIMESTEP as f32;
+	wave_director.seconds_since_wave_start += timestep;
+	wave_director.elapsed_seconds += timestep;
+
+	// The stage is over once its stronghold falls
+	if level_progress.stage_complete {
+		return;
+	}
+
+	// Waves are placed around the player, nothing to do without one
+	let player_transform = match player_ship.get_single() {
+		Ok(val) => val,
+		Err(_) => return,
+	};
+	wave_director.player_distance_travelled = level_progress.distance_travelled;
+
+	let difficulty_level =
+		match difficulty_curve_definition_list
+			.iter()
+			.find(|&difficulty_curve_definition| {
+				difficulty_curve_definition.curve_name == difficulty_curve.0
+			}) {
+			Some(val) => val.difficulty_at(
+				wave_director.elapsed_seconds,
+				wave_director.player_distance_travelled,
+			),
+			None => panic!(
+				"Failed to find {:?} in Difficulty Curve definitions list!",
+				difficulty_curve.0
+			),
+		};
+
+	// Check which waves have been cleared, waves started early by a timer can still be in play
+	let cleared_waves: Vec<usize> = wave_director
+		.uncleared_waves
+		.iter()
+		.copied()
+		.filter(|&wave_number| {
+			!wave_director
+				.pending_spawns
+				.iter()
+				.any(|pending_spawn| pending_spawn.wave_number == wave_number)
+				&& !wave_members
+					.iter()
+					.any(|wave_member| wave_member.0 == wave_number)
+		})
+		.collect();
+	for wave_number in cleared_waves {
+		wave_director.uncleared_waves.remove(&wave_number);
+		wave_ended_events.send(WaveEndedEvent { wave_number });
+	}
+
+	// Start the next wave if it has been triggered
+	let wave_set_definition = match wave_set_definition_list
+		.iter()
+		.find(|&wave_set_definition| {
+			wave_set_definition.wave_set_name == wave_director.wave_set_name
+		}) {
+		Some(val) => val,
+		None => panic!(
+			"Failed to find {:?} in Wave Set definitions list!",
+			wave_director.wave_set_name
+		),
+	};
+	if wave_director.next_wave_index >= wave_set_definition.waves.len()
+		&& wave_set_definition.repeat
+	{
+		wave_director.next_wave_index = 0;
+	}
+	if let Some(wave_definition) = wave_set_definition.waves.get(wave_director.next_wave_index) {
+		if wave_director.is_triggered(wave_definition.trigger) {
+			wave_director.next_wave_index += 1;
+			wave_director.waves_started += 1;
+			wave_director.seconds_since_wave_start = 0.0;
+			let wave_number = wave_director.waves_started;
+			wave_director.uncleared_waves.insert(wave_number);
+
+			for wave_group in wave_definition.groups.iter() {
+				let count =
+					(wave_group.count as f32 * difficulty_level.count_multiplier).round() as usize;
+				let formation_id = wave_director.next_formation_id;
+				let mut keeps_formation = false;
+				for (enemy_number, (offset_from_player, heading, offset_from_leader)) in
+					wave_group_spawn_points(wave_group, count, &formation_definition_list, &mut rng)
+						.into_iter()
+						.enumerate()
+				{
+					keeps_formation |= offset_from_leader.is_some();
+					wave_director.pending_spawns.push(PendingWaveSpawn {
+						wave_number,
+						enemy_name: wave_group.enemy_name,
+						offset_from_player,
+						heading,
+						seconds_remaining: wave_group.delay_seconds
+							+ wave_group.interval_seconds * enemy_number as f32,
+						formation_slot: offset_from_leader.map(|offset_from_leader| {
+							PendingFormationSlot {
+								formation_id,
+								offset_from_leader,
+							}
+						}),
+					});
+				}
+				if keeps_formation {
+					wave_director.next_formation_id += 1;
+				}
+			}
+			wave_started_events.send(WaveStartedEvent { wave_number });
+		}
+	}
+
+	// Spawn enemies that are due
+	let mut still_pending = Vec::new();
+	for mut pending_spawn in std::mem::take(&mut wave_director.pending_spawns) {
+		pending_spawn.seconds_remaining -= timestep;
+		if pending_spawn.seconds_remaining > 0.0 {
+			still_pending.push(pending_spawn);
+			continue;
+		}
+
+		let mut enemy = spawning::spawn_enemy(
+			spawning::substitute_enemy(pending_spawn.enemy_name, &difficulty_level, &mut rng),
+			Transform::from_translation(
+				player_transform.translation + pending_spawn.offset_from_player.extend(0.0),
+			),
+			&enemy_definition_list,
+			&ship_definition_list,
+			&gun_definition_list,
+			&cartridge_definition_list,
+		);
+		spawning::apply_difficulty(&mut enemy, &difficulty_level);
+		enemy.ship.velocity = physics::Velocity(pending_spawn.heading * enemy.ship.max_speed.0);
+		let mut enemy_commands = commands.spawn_bundle(enemy);
+		enemy_commands.insert(WaveMember(pending_spawn.wave_number));
+
+		// The first of a kept formation to spawn leads it, the rest take station on the leader
+		if let Some(formation_slot) = pending_spawn.formation_slot {
+			let leader = wave_director
+				.formation_leaders
+				.get(&formation_slot.formation_id)
+				.copied();
+			match leader {
+				Some(leader) => {
+					enemy_commands.insert(enemy::FormationMember {
+						leader,
+						offset: formation_slot.offset_from_leader,
+					});
+				}
+				None => {
+					wave_director
+						.formation_leaders
+						.insert(formation_slot.formation_id, enemy_commands.id());
+				}
+			}
+		}
+	}
+	wave_director.pending_spawns = still_pending;
+
+	// Forget leaders once their whole formation has spawned
+	let formations_pending: Vec<usize> = wave_director
+		.pending_spawns
+		.iter()
+		.filter_map(|pending_spawn| pending_spawn.formation_slot)
+		.map(|formation_slot| formation_slot.formation_id)
+		.collect();
+	wave_director
+		.formation_leaders
+		.retain(|formation_id, _| formations_pending.contains(formation_id));
+}
+
+/// Despawns wave members that have flown out of the play area, so their wave can still be cleared
+pub fn wave_member_departure_system(
+	mut commands: Commands,
+	player_ship: Query<&Transform, With<ship::IsPlayerShip>>,
+	wave_members: Query<(Entity, &Transform), With<WaveMember>>,
+) {
+	let player_transform = match player_ship.get_single() {
+		Ok(val) => val,
+		Err(_) => return,
+	};
+	let play_area_half_size = Vec2::new(
+		SPAWN_EDGE_DISTANCE_X + PLAY_AREA_MARGIN,
+		SPAWN_EDGE_DISTANCE_Y + PLAY_AREA_MARGIN,
+	);
+	for (entity, transform) in wave_members.iter() {
+		let offset_from_player = (transform.translation - player_transform.translation).truncate();
+		if offset_from_player.abs().cmpgt(play_area_half_size).any() {
+			commands.entity(entity).despawn_recursive();
+		}
+	}
+}
@@ -0,0 +1,134 @@
+use super::*;
+
+const WAVE_DATA_PATH: &str = "data/waves/";
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Debug)]
+pub enum WaveSetName {
+	TemplateWaveSet,
+	Default,
+}
+
+impl Default for WaveSetName {
+	fn default() -> Self {
+		WaveSetName::Default
+	}
+}
+
+/// Edge of the play area, relative to the player ship
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Debug)]
+pub enum SpawnEdge {
+	Top,
+	Bottom,
+	Left,
+	Right,
+}
+
+/// Where a group of enemies appears
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Debug)]
+pub enum SpawnPosition {
+	/// Somewhere along an edge of the play area, heading inwards
+	Edge(SpawnEdge),
+	/// A point relative to the player ship, heading towards the player
+	Point(Vec2),
+}
+
+/// How the enemies of a group are arranged around their spawn position
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Debug)]
+pub enum WaveFormation {
+	/// Each enemy picks its own random spot, spread along the edge
+	Scattered,
+	/// Arranged into the slots of a Formation Definition, the first enemy leading
+	Formation(formation_list::FormationName),
+}
+
+/// What has to happen before a wave starts
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Debug)]
+pub enum WaveTrigger {
+	/// Seconds after the previous wave started
+	Time(f32),
+	/// Every enemy of the previous wave has been destroyed
+	PreviousWaveCleared,
+	/// The player ship has travelled this many meters since the battle started
+	PlayerProgress(f32),
+}
+
+/// A group of identical enemies within a wave
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct WaveGroupDefinition {
+	pub enemy_name: enemy_list::EnemyName,
+	pub count: usize,
+	pub spawn_position: SpawnPosition,
+	pub formation: WaveFormation,
+	/// Seconds after the wave starts before the first enemy of the group spawns
+	pub delay_seconds: f32,
+	/// Seconds between each enemy of the group spawning
+	pub interval_seconds: f32,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct WaveDefinition {
+	pub trigger: WaveTrigger,
+	pub groups: Vec<WaveGroupDefinition>,
+}
+
+/// Wave set definition for storing a scripted sequence of waves as YAML
+#[derive(Serialize, Deserialize, Debug)]
+pub struct WaveSetDefinition {
+	pub wave_set_name: WaveSetName,
+	/// Start again from the first wave after the last one, for endless waves
+	pub repeat: bool,
+	pub waves: Vec<WaveDefinition>,
+}
+
+/// List of Wave Set Definitions
+#[derive(Deref, DerefMut, Debug)]
+pub struct WaveSetDefinitionList(Vec<WaveSetDefinition>);
+
+/// Generates an explanatory template for how a Wave Set Definition should be formatted in YAML,
+/// then writes it out to a template.yaml file.
+pub fn write_wave_set_definition_template() {
+	// Define template
+	let wave_set_definition_template = WaveSetDefinition {
+		wave_set_name: WaveSetName::TemplateWaveSet,
+		repeat: true,
+		waves: vec![
+			WaveDefinition {
+				trigger: WaveTrigger::Time(2.0),
+				groups: vec![WaveGroupDefinition {
+					enemy_name: enemy_list::EnemyName::TemplateEnemy,
+					count: 5,
+					spawn_position: SpawnPosition::Edge(SpawnEdge::Top),
+					formation: WaveFormation::Scattered,
+					delay_seconds: 0.0,
+					interval_seconds: 0.5,
+				}],
+			},
+			WaveDefinition {
+				trigger: WaveTrigger::PreviousWaveCleared,
+				groups: vec![WaveGroupDefinition {
+					enemy_name: enemy_list::EnemyName::TemplateEnemy,
+					count: 3,
+					spawn_position: SpawnPosition::Point(Vec2::new(-400.0, 600.0)),
+					formation: WaveFormation::Formation(
+						formation_list::FormationName::TemplateFormation,
+					),
+					delay_seconds: 1.0,
+					interval_seconds: 0.0,
+				}],
+			},
+			WaveDefinition {
+				trigger: WaveTrigger::PlayerProgress(1000.0),
+				groups: vec![],
+			},
+		],
+	};
+	// Write out template
+	crate::game_io::write_definition_template(WAVE_DATA_PATH, wave_set_definition_template);
+}
+
+/// Reads all *.yaml Wave Set definition files in data/waves/ and returns them as a ```WaveSetDefinitionList```
+pub fn read_wave_set_definitions() -> WaveSetDefinitionList {
+	let wave_set_definition_list =
+		WaveSetDefinitionList(crate::game_io::read_definitions(WAVE_DATA_PATH));
+	return wave_set_definition_list;
+}